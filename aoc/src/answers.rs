@@ -0,0 +1,137 @@
+use anyhow::{Context, Result};
+use puzzle::{Answer, Day, Part};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::fs;
+use std::path::Path;
+
+/// Expected answers, keyed by year.
+#[derive(Debug, Default)]
+pub struct Answers(BTreeMap<u32, Year>);
+
+/// One year's expected answers, keyed by day (`day13`) and then input variant.
+#[derive(Debug, Default, Deserialize)]
+#[serde(transparent)]
+struct Year(BTreeMap<String, BTreeMap<String, Expected>>);
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Expected {
+    part1: Option<Recorded>,
+    part2: Option<Recorded>,
+}
+
+/// A recorded answer: an integer, or a string for text and integers too big for TOML.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(untagged)]
+pub enum Recorded {
+    Integer(i64),
+    Text(String),
+}
+
+impl Recorded {
+    pub fn matches(&self, answer: &Answer) -> bool {
+        answer.is_solved() && self.to_string() == answer.to_string()
+    }
+}
+
+impl Display for Recorded {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Recorded::Integer(value) => write!(f, "{}", value),
+            Recorded::Text(text) => write!(f, "{}", text),
+        }
+    }
+}
+
+impl Answers {
+    /// Name of the answers file within an inputs directory.
+    pub const FILENAME: &'static str = "answers.toml";
+
+    /// Load the answers recorded alongside each year's inputs, in `inputs_dir/<year>`, if
+    /// there are any.
+    pub fn load(inputs_dir: &Path) -> Result<Self> {
+        let mut answers = Self::default();
+        let entries = match fs::read_dir(inputs_dir) {
+            Ok(entries) => entries,
+            Err(_) => return Ok(answers),
+        };
+        for entry in entries {
+            let entry = entry?;
+            let Some(year) = entry
+                .file_name()
+                .to_str()
+                .and_then(|name| name.parse().ok())
+            else {
+                continue;
+            };
+            let path = entry.path().join(Self::FILENAME);
+            if !path.exists() {
+                continue;
+            }
+            let text =
+                fs::read_to_string(&path).context(format!("loading '{}'", path.display()))?;
+            let recorded =
+                toml::from_str(&text).context(format!("parsing '{}'", path.display()))?;
+            answers.0.insert(year, recorded);
+        }
+        Ok(answers)
+    }
+
+    /// Input variants with answers recorded for the given day.
+    pub fn variants(&self, day: &Day) -> Vec<String> {
+        self.day(day)
+            .map(|variants| variants.keys().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    pub fn get(&self, day: &Day, variant: &str, part: Part) -> Option<Recorded> {
+        let expected = self.day(day)?.get(variant)?;
+        match part {
+            Part::One => expected.part1.clone(),
+            Part::Two => expected.part2.clone(),
+        }
+    }
+
+    fn day(&self, day: &Day) -> Option<&BTreeMap<String, Expected>> {
+        self.0.get(&day.year)?.0.get(&format!("day{}", day.day))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lookup() {
+        let year: Year = toml::from_str(
+            r#"
+            [day1.small]
+            part1 = 514579
+
+            [day1.full]
+            part1 = 1
+            part2 = "123456789012345678901234567890"
+            "#,
+        )
+        .unwrap();
+        let answers = Answers(BTreeMap::from([(2020, year)]));
+        let (day1, day2) = (Day::of::<day1::Solution>(), Day::of::<day2::Solution>());
+        assert_eq!(answers.variants(&day1), vec!["full", "small"]);
+        assert_eq!(
+            answers.get(&day1, "small", Part::One),
+            Some(Recorded::Integer(514579))
+        );
+        assert_eq!(answers.get(&day1, "small", Part::Two), None);
+        let big = answers.get(&day1, "full", Part::Two).unwrap();
+        assert!(big.matches(&Answer::BigInteger(
+            "123456789012345678901234567890".parse().unwrap()
+        )));
+        assert!(Recorded::Integer(2).matches(&Answer::Integer(2)));
+        assert!(!Recorded::Integer(2).matches(&Answer::Integer(3)));
+        assert!(!Recorded::Text("x".into()).matches(&Answer::Unsolved("x".into())));
+        assert_eq!(answers.get(&day2, "full", Part::One), None);
+        assert!(answers.variants(&day2).is_empty());
+    }
+}
@@ -0,0 +1,152 @@
+use anyhow::{Context, Result};
+use clap::Args;
+use log::warn;
+use num_bigint::BigInt;
+use puzzle::{Answer, Day, Part};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fmt::Display;
+use std::fs;
+use std::path::PathBuf;
+use std::process;
+
+/// Command line options controlling the answer cache.
+#[derive(Debug, Clone, Args)]
+pub struct CacheArgs {
+    /// Solve every part afresh rather than serving cached answers, refreshing the cache
+    #[arg(long)]
+    no_cache: bool,
+    /// Directory holding cached answers
+    #[arg(long, env = "AOC_CACHE_DIR", default_value = "target/aoc-cache")]
+    cache_dir: PathBuf,
+}
+
+impl CacheArgs {
+    pub fn cache(&self) -> Cache {
+        Cache {
+            dir: self.cache_dir.clone(),
+            serve: !self.no_cache,
+        }
+    }
+}
+
+/// Whether an answer was worked out by this run, or served from the cache.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Origin {
+    Fresh,
+    Cached,
+}
+
+impl Display for Origin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Origin::Fresh => write!(f, "fresh"),
+            Origin::Cached => write!(f, "cached"),
+        }
+    }
+}
+
+/// Solved answers on disk, one file per answer, keyed by year, day, part, the version of the
+/// day's crate and a hash of the input.
+#[derive(Debug, Clone)]
+pub struct Cache {
+    dir: PathBuf,
+    /// Whether to serve answers, or only store them.
+    serve: bool,
+}
+
+/// An [`Answer`] as stored, tagged so that big integers and text read back as they were.
+#[derive(Debug, Serialize, Deserialize)]
+enum Stored {
+    Integer(i64),
+    BigInteger(String),
+    Text(String),
+}
+
+/// The key identifying an input by its contents.
+pub fn hash(contents: &[u8]) -> String {
+    Sha256::digest(contents)
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+impl Cache {
+    /// The cached answer to a part for the input with the given hash, if any.
+    pub fn get(&self, day: &Day, part: Part, hash: &str) -> Option<Answer> {
+        if !self.serve {
+            return None;
+        }
+        let path = self.path(day, part, hash);
+        let stored = fs::read_to_string(&path).ok()?;
+        match serde_json::from_str(&stored) {
+            Ok(Stored::Integer(value)) => Some(Answer::Integer(value)),
+            Ok(Stored::BigInteger(value)) => value.parse::<BigInt>().ok().map(Answer::BigInteger),
+            Ok(Stored::Text(text)) => Some(Answer::Text(text)),
+            Err(e) => {
+                warn!("ignoring '{}': {}", path.display(), e);
+                None
+            }
+        }
+    }
+
+    /// Store the answer to a part for the input with the given hash, if it was solved.
+    pub fn put(&self, day: &Day, part: Part, hash: &str, answer: &Answer) -> Result<()> {
+        let stored = match answer {
+            Answer::Integer(value) => Stored::Integer(*value),
+            Answer::BigInteger(value) => Stored::BigInteger(value.to_string()),
+            Answer::Text(text) => Stored::Text(text.clone()),
+            Answer::Unsolved(_) | Answer::TimedOut(_) => return Ok(()),
+        };
+        let path = self.path(day, part, hash);
+        fs::create_dir_all(&self.dir)
+            .with_context(|| format!("creating '{}'", self.dir.display()))?;
+        // Write then rename, so that another run never reads a half written answer
+        let partial = path.with_extension(format!("{}.partial", process::id()));
+        fs::write(&partial, serde_json::to_string(&stored)?)
+            .with_context(|| format!("writing '{}'", partial.display()))?;
+        fs::rename(&partial, &path).with_context(|| format!("writing '{}'", path.display()))
+    }
+
+    fn path(&self, day: &Day, part: Part, hash: &str) -> PathBuf {
+        self.dir.join(format!(
+            "{}.day{}.{}.{}.{}.json",
+            day.year, day.day, part, day.version, hash
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let dir = std::env::temp_dir().join(format!("aoc-cache-test-{}", process::id()));
+        let cache = Cache {
+            dir: dir.clone(),
+            serve: true,
+        };
+        let day = Day::of::<day13::Solution>();
+        let hash = hash(b"939\n7,13,x,x,59,x,31,19\n");
+        let big = Answer::BigInteger(BigInt::from(i64::MAX) * 3);
+        for (part, answer) in [(Part::One, Answer::Integer(295)), (Part::Two, big)] {
+            assert_eq!(cache.get(&day, part, &hash), None);
+            cache.put(&day, part, &hash, &answer).unwrap();
+            assert_eq!(cache.get(&day, part, &hash), Some(answer));
+        }
+        assert_eq!(cache.get(&day, Part::One, &super::hash(b"")), None);
+
+        let unsolved = Answer::Unsolved("no answer found".to_string());
+        cache.put(&day, Part::One, "unsolved", &unsolved).unwrap();
+        assert_eq!(cache.get(&day, Part::One, "unsolved"), None);
+
+        let stored_only = Cache {
+            serve: false,
+            ..cache
+        };
+        assert_eq!(stored_only.get(&day, Part::One, &hash), None);
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
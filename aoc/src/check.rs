@@ -0,0 +1,71 @@
+use crate::input::Input;
+use anyhow::{Context, Result};
+use puzzle::{Day, ParseError};
+use std::io::{self, Write};
+
+/// Every problem with a day's input, in the order they appear, without solving it.
+pub fn check(day: &Day, input: &Input) -> Result<Vec<ParseError>> {
+    let contents = String::from_utf8(input.read(day)?).context("input is not UTF-8")?;
+    let mut problems = day.check(&contents);
+    if let Some(path) = input.path(day) {
+        let path = path.to_string_lossy();
+        problems = problems.into_iter().map(|e| e.in_file(&path)).collect();
+    }
+    // Problems with the input as a whole, rather than any one line, come last
+    problems.sort_by_key(|e| (e.line.is_none(), e.line, e.column));
+    Ok(problems)
+}
+
+/// Write whether a day's input is well formed, followed by each of its problems.
+pub fn write(
+    out: &mut impl Write,
+    day: &Day,
+    problems: &Result<Vec<ParseError>>,
+) -> io::Result<()> {
+    match problems {
+        Ok(problems) if problems.is_empty() => writeln!(out, "{}: ok", day),
+        Ok(problems) => {
+            writeln!(out, "{}: {} problem(s)", day, problems.len())?;
+            for problem in problems {
+                writeln!(out, "  {}", problem)?;
+            }
+            Ok(())
+        }
+        Err(e) => writeln!(out, "{}: {:#}", day, e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn examples_are_well_formed() {
+        let input = Input::Variant(PathBuf::from("../inputs"), "small".to_string());
+        for day in [
+            Day::of::<day4::Solution>(),
+            Day::of::<day13::Solution>(),
+            Day::of::<day16::Solution>(),
+            Day::of::<day19::Solution>(),
+        ] {
+            assert_eq!(check(&day, &input).unwrap(), vec![], "{}", day);
+        }
+    }
+
+    #[test]
+    fn write_problems() {
+        let day = Day::of::<day13::Solution>();
+        let mut out = Vec::new();
+        let problems = day.check("939\n7,13,0,x\nextra\n");
+        write(&mut out, &day, &Ok(problems)).unwrap();
+        write(&mut out, &day, &Ok(vec![])).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "2020 day 13: 2 problem(s)\n\
+             \x20 2:6: expected a positive bus id: '0'\n\
+             \x20 3:1: unexpected line after the buses: 'extra'\n\
+             2020 day 13: ok\n"
+        );
+    }
+}
@@ -0,0 +1,25 @@
+use puzzle::Day;
+
+/// Every day known to the runner, in calendar order.
+pub fn all() -> Vec<Day> {
+    vec![
+        Day::of::<day1::Solution>(),
+        Day::of::<day2::Solution>(),
+        Day::of::<day3::Solution>(),
+        Day::of::<day4::Solution>(),
+        Day::of::<day5::Solution>(),
+        Day::of::<day6::Solution>(),
+        Day::of::<day7::Solution>(),
+        Day::of::<day8::Solution>(),
+        Day::of::<day9::Solution>(),
+        Day::of::<day11::Solution>(),
+        Day::of::<day12::Solution>(),
+        Day::of::<day13::Solution>(),
+        Day::of::<day14::Solution>(),
+        Day::of::<day15::Solution>(),
+        Day::of::<day16::Solution>(),
+        Day::of::<day17::Solution>(),
+        Day::of::<day18::Solution>(),
+        Day::of::<day19::Solution>(),
+    ]
+}
@@ -0,0 +1,157 @@
+use anyhow::Result;
+use clap::{Args, ValueEnum};
+use puzzle::{Day, Explanation, Part};
+use serde::ser::{SerializeMap, SerializeSeq};
+use serde::{Serialize, Serializer};
+use std::io::Write;
+
+/// Command line options for explaining how each answer was reached.
+#[derive(Debug, Clone, Args)]
+pub struct ExplainArgs {
+    /// Print how each answer was reached, for the days which can say, to stdout
+    #[arg(long, value_enum, num_args = 0..=1, default_missing_value = "text")]
+    explain: Option<Format>,
+}
+
+impl ExplainArgs {
+    pub fn format(&self) -> Option<Format> {
+        self.explain
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+    /// Indented facts under a heading per part
+    Text,
+    /// One JSON object per line
+    Json,
+}
+
+/// Write the explanation of one part's answer, or say that the day has none.
+pub fn write(
+    out: &mut impl Write,
+    format: Format,
+    day: &Day,
+    part: Part,
+    explanation: Option<&Explanation>,
+) -> Result<()> {
+    match format {
+        Format::Text => match explanation {
+            Some(explanation) => {
+                writeln!(out, "{} {}:", day, part)?;
+                for line in explanation.to_string().lines() {
+                    writeln!(out, "  {}", line)?;
+                }
+            }
+            None => writeln!(out, "{} {}: no explanation", day, part)?,
+        },
+        Format::Json => {
+            let record = Record {
+                year: day.year,
+                day: day.day,
+                part: part.to_string(),
+                explanation: explanation.map(Json),
+            };
+            serde_json::to_writer(&mut *out, &record)?;
+            writeln!(out)?;
+        }
+    }
+    Ok(())
+}
+
+/// Serialize an optional explanation as JSON, for `#[serde(serialize_with)]`.
+pub fn json<S: Serializer>(
+    explanation: &Option<Explanation>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    explanation.as_ref().map(Json).serialize(serializer)
+}
+
+#[derive(Serialize)]
+struct Record<'a> {
+    year: u32,
+    day: u32,
+    part: String,
+    explanation: Option<Json<'a>>,
+}
+
+/// An explanation as JSON, keeping the order of each record's facts.
+struct Json<'a>(&'a Explanation);
+
+impl Serialize for Json<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self.0 {
+            Explanation::Integer(value) => serializer.serialize_i64(*value),
+            Explanation::Text(text) => serializer.serialize_str(text),
+            Explanation::List(items) => {
+                let mut seq = serializer.serialize_seq(Some(items.len()))?;
+                for item in items {
+                    seq.serialize_element(&Json(item))?;
+                }
+                seq.end()
+            }
+            Explanation::Record(facts) => {
+                let mut map = serializer.serialize_map(Some(facts.len()))?;
+                for (name, fact) in facts {
+                    map.serialize_entry(name, &Json(fact))?;
+                }
+                map.end()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn explanation() -> Explanation {
+        Explanation::record([
+            ("patched", 7_i64.into()),
+            ("was", "jmp -4".into()),
+            ("now", "nop -4".into()),
+            ("accumulator", 8_i64.into()),
+        ])
+    }
+
+    #[test]
+    fn text() {
+        let mut out = Vec::new();
+        let (day8, day2) = (Day::of::<day8::Solution>(), Day::of::<day2::Solution>());
+        write(
+            &mut out,
+            Format::Text,
+            &day8,
+            Part::Two,
+            Some(&explanation()),
+        )
+        .unwrap();
+        write(&mut out, Format::Text, &day2, Part::One, None).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "2020 day 8 part2:\n  patched: 7\n  was: jmp -4\n  now: nop -4\n  accumulator: 8\n\
+             2020 day 2 part1: no explanation\n"
+        );
+    }
+
+    #[test]
+    fn json() {
+        let mut out = Vec::new();
+        let (day8, day2) = (Day::of::<day8::Solution>(), Day::of::<day2::Solution>());
+        write(
+            &mut out,
+            Format::Json,
+            &day8,
+            Part::Two,
+            Some(&explanation()),
+        )
+        .unwrap();
+        write(&mut out, Format::Json, &day2, Part::One, None).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "{\"year\":2020,\"day\":8,\"part\":\"part2\",\"explanation\":\
+             {\"patched\":7,\"was\":\"jmp -4\",\"now\":\"nop -4\",\"accumulator\":8}}\n\
+             {\"year\":2020,\"day\":2,\"part\":\"part1\",\"explanation\":null}\n"
+        );
+    }
+}
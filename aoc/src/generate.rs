@@ -0,0 +1,84 @@
+use crate::input::variant_path;
+use anyhow::{bail, Context, Result};
+use log::{error, info};
+use puzzle::Day;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+/// Where generated inputs are written.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Destination {
+    Stdout,
+    Path(PathBuf),
+    /// `<year>/input.day<N>.<variant>` within the given inputs directory.
+    Variant(PathBuf, String),
+}
+
+/// Generate an input of the given size for each day, from the same seed.
+pub fn generate(days: &[Day], size: usize, seed: u64, destination: &Destination) -> Result<()> {
+    if days.len() != 1 && !matches!(destination, Destination::Variant(..)) {
+        bail!("inputs for more than one day can only be written to a named variant");
+    }
+    let mut failed = 0;
+    for day in days {
+        // Generate in full before writing, so that a failure leaves no partial input behind
+        let mut input = Vec::new();
+        if let Err(e) = day.generate(size, seed, &mut input) {
+            error!("{}: {:#}", day, e);
+            failed += 1;
+            continue;
+        }
+        match destination {
+            Destination::Stdout => io::stdout().lock().write_all(&input)?,
+            Destination::Path(path) => write(path, &input)?,
+            Destination::Variant(dir, variant) => {
+                let path = variant_path(dir, day, variant);
+                // The first input of a new year has no directory to go in yet
+                if let Some(parent) = path.parent() {
+                    fs::create_dir_all(parent)
+                        .with_context(|| format!("creating '{}'", parent.display()))?;
+                }
+                write(&path, &input)?;
+                info!("{}: wrote '{}'", day, path.display());
+            }
+        }
+    }
+    if failed > 0 {
+        bail!("{} day(s) failed", failed);
+    }
+    Ok(())
+}
+
+fn write(path: &Path, input: &[u8]) -> Result<()> {
+    fs::write(path, input).with_context(|| format!("writing '{}'", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::days;
+    use puzzle::Part;
+
+    #[test]
+    fn solvable() {
+        for day in days::all() {
+            let mut input = Vec::new();
+            day.generate(30, 2020, &mut input).unwrap();
+            let mut again = Vec::new();
+            day.generate(30, 2020, &mut again).unwrap();
+            assert!(input == again, "{} is not reproducible", day);
+
+            let mut solver = day.from_reader(&mut input.as_slice()).unwrap();
+            solver.analyse();
+            // Day 15's part 2 is the same 30 million turns whatever the input, so is left out
+            let parts: &[Part] = match (day.year, day.day) {
+                (2020, 15) => &[Part::One],
+                _ => &Part::ALL,
+            };
+            for &part in parts {
+                let answer = solver.answer(part);
+                assert!(answer.is_solved(), "{} {}: {}", day, part, answer);
+            }
+        }
+    }
+}
@@ -0,0 +1,97 @@
+use anyhow::{bail, Context, Result};
+use clap::Args;
+use puzzle::{in_file, Day, Solver};
+use std::fmt::Display;
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+/// Command line options selecting which input a day is run against.
+#[derive(Debug, Clone, Args)]
+pub struct InputArgs {
+    /// Read the input from this file, or `-` for stdin
+    #[arg(long, short, conflicts_with = "variant")]
+    input: Option<String>,
+    /// Named input variant, read from `<inputs-dir>/<year>/input.day<N>.<variant>`
+    #[arg(long, short, default_value = "full")]
+    variant: String,
+    /// Directory holding the named input variants
+    #[arg(long, env = "AOC_INPUTS_DIR", default_value = "inputs")]
+    inputs_dir: PathBuf,
+}
+
+impl InputArgs {
+    pub fn input(&self) -> Input {
+        match self.input.as_deref() {
+            Some("-") => Input::Stdin,
+            Some(path) => Input::Path(PathBuf::from(path)),
+            None => Input::Variant(self.inputs_dir.clone(), self.variant.clone()),
+        }
+    }
+}
+
+/// Where a day's puzzle input is read from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Input {
+    Stdin,
+    Path(PathBuf),
+    /// `<year>/input.day<N>.<variant>` within the given inputs directory.
+    Variant(PathBuf, String),
+}
+
+impl Input {
+    /// Path to load the given day's input from, if it comes from a file.
+    pub fn path(&self, day: &Day) -> Option<PathBuf> {
+        match self {
+            Input::Stdin => None,
+            Input::Path(path) => Some(path.clone()),
+            Input::Variant(dir, variant) => Some(variant_path(dir, day, variant)),
+        }
+    }
+
+    /// The given day's input, in full.
+    pub fn read(&self, day: &Day) -> Result<Vec<u8>> {
+        match self.path(day) {
+            Some(path) => fs::read(&path).with_context(|| format!("loading '{}'", path.display())),
+            None => {
+                let mut contents = Vec::new();
+                io::stdin().lock().read_to_end(&mut contents)?;
+                Ok(contents)
+            }
+        }
+    }
+
+    /// Parse the given day's input, as read by [`Input::read`].
+    pub fn parse(&self, day: &Day, contents: &[u8]) -> Result<Box<dyn Solver>> {
+        let solution = day.from_reader(&mut &contents[..]);
+        match self.path(day) {
+            Some(path) => solution.map_err(|e| in_file(e, &path.to_string_lossy())),
+            None => solution,
+        }
+    }
+
+    /// Only named variants can be resolved for more than one day.
+    pub fn check_days(&self, num_days: usize) -> Result<()> {
+        if !matches!(self, Input::Variant(..)) && num_days != 1 {
+            bail!("{} can only be used when running a single day", self);
+        }
+        Ok(())
+    }
+}
+
+impl Display for Input {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Input::Stdin => write!(f, "stdin"),
+            Input::Path(path) => write!(f, "{}", path.display()),
+            Input::Variant(_, variant) => write!(f, "{}", variant),
+        }
+    }
+}
+
+/// Where a named input variant lives: inputs are kept apart by year, so that days of different
+/// years never clash.
+pub fn variant_path(dir: &Path, day: &Day, variant: &str) -> PathBuf {
+    dir.join(day.year.to_string())
+        .join(format!("input.day{}.{}", day.day, variant))
+}
@@ -0,0 +1,566 @@
+use anyhow::{anyhow, Result};
+use cache::{Cache, CacheArgs, Origin};
+use clap::{Parser, Subcommand};
+use explain::ExplainArgs;
+use generate::Destination;
+use input::{Input, InputArgs};
+use log::{debug, error, info, warn};
+use profile::{timed, PeakMemory, Profile};
+use puzzle::{Answer, Cancel, Day, Part};
+use report::{Record, ReportArgs, Reporter};
+use std::io::Write;
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::thread;
+use std::time::{Duration, Instant};
+use summary::Solved;
+use trace::TraceArgs;
+use tracing::info_span;
+
+mod answers;
+mod cache;
+mod check;
+mod days;
+mod explain;
+mod generate;
+mod input;
+mod profile;
+mod report;
+mod scaffold;
+mod schedule;
+mod serve;
+mod summary;
+mod trace;
+mod verify;
+mod watch;
+
+#[derive(Debug, Parser)]
+#[command(name = "aoc", about = "Runs the Advent of Code 2020 solutions")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Solve a single day, or every day
+    Run {
+        /// Day to run, or `all`
+        day: Selection,
+        /// Only answer this part (1 or 2)
+        #[arg(long)]
+        part: Option<Part>,
+        #[command(flatten)]
+        input: InputArgs,
+        #[command(flatten)]
+        report: ReportArgs,
+        #[command(flatten)]
+        cache: CacheArgs,
+        #[command(flatten)]
+        trace: TraceArgs,
+        #[command(flatten)]
+        explain: ExplainArgs,
+        /// Print a table of the time spent in each phase and peak memory use
+        #[arg(long)]
+        profile: bool,
+        /// Number of days to solve at once [default: available parallelism]
+        #[arg(long, short)]
+        jobs: Option<NonZeroUsize>,
+        /// Give up on a day after this many seconds, reporting its unanswered parts as timed out
+        #[arg(long, value_parser = seconds)]
+        timeout: Option<Duration>,
+    },
+    /// Solve a day, then solve it again whenever its input changes, showing how the answers changed
+    Watch {
+        /// Day to watch, or `all`
+        day: Selection,
+        /// Only answer this part (1 or 2)
+        #[arg(long)]
+        part: Option<Part>,
+        #[command(flatten)]
+        input: InputArgs,
+        #[command(flatten)]
+        cache: CacheArgs,
+        /// Seconds between checks for changes to the input
+        #[arg(long, value_parser = seconds, default_value = "1")]
+        interval: Duration,
+        /// Give up on a day after this many seconds, reporting its unanswered parts as timed out
+        #[arg(long, value_parser = seconds)]
+        timeout: Option<Duration>,
+    },
+    /// Check that a day's input is well formed, reporting every problem, without solving it
+    Check {
+        /// Day to check, or `all`
+        day: Selection,
+        #[command(flatten)]
+        input: InputArgs,
+    },
+    /// Check answers against those recorded in the inputs directory
+    Verify {
+        /// Day to verify, or `all`
+        #[arg(default_value = "all")]
+        day: Selection,
+        /// Only verify this input variant, rather than every variant with recorded answers
+        #[arg(long, short)]
+        variant: Option<String>,
+        /// Directory holding the input variants and their answers
+        #[arg(long, env = "AOC_INPUTS_DIR", default_value = "inputs")]
+        inputs_dir: PathBuf,
+    },
+    /// Write a synthetic input, for stress testing a day at scale
+    Gen {
+        /// Day to generate an input for, or `all` with `--variant`
+        day: Selection,
+        /// Size of the input; what it counts (lines, rows, tickets, ...) is up to each day
+        #[arg(long, short)]
+        size: usize,
+        /// Seed for every random choice, so that an input can be generated again
+        #[arg(long, default_value_t = 0)]
+        seed: u64,
+        /// Write the input to this file, rather than stdout
+        #[arg(long, short, conflicts_with = "variant")]
+        output: Option<PathBuf>,
+        /// Write the input to the named variant, `<inputs-dir>/<year>/input.day<N>.<variant>`
+        #[arg(long, short)]
+        variant: Option<String>,
+        /// Directory holding the named input variants
+        #[arg(long, env = "AOC_INPUTS_DIR", default_value = "inputs")]
+        inputs_dir: PathBuf,
+    },
+    /// Serve answers, timings, verification and renderings on localhost, as HTML and JSON
+    Serve {
+        /// Day to serve, or `all`
+        #[arg(default_value = "all")]
+        day: Selection,
+        /// Port to listen on, on 127.0.0.1
+        #[arg(long, default_value_t = 2020)]
+        port: u16,
+        /// Named input variant, read from `<inputs-dir>/<year>/input.day<N>.<variant>`
+        #[arg(long, short, default_value = "full")]
+        variant: String,
+        /// Directory holding the input variants and their answers
+        #[arg(long, env = "AOC_INPUTS_DIR", default_value = "inputs")]
+        inputs_dir: PathBuf,
+        #[command(flatten)]
+        cache: CacheArgs,
+        /// Number of days to solve at once [default: available parallelism]
+        #[arg(long, short)]
+        jobs: Option<NonZeroUsize>,
+        /// Give up on a day after this many seconds, reporting its unanswered parts as timed out
+        #[arg(long, value_parser = seconds)]
+        timeout: Option<Duration>,
+    },
+    /// Create a new day crate from `template`, wired into the workspace and runner
+    NewDay {
+        /// Day to create
+        day: u32,
+        /// Year of the day [default: the latest year with any days]
+        #[arg(long)]
+        year: Option<u32>,
+        /// Puzzle title
+        #[arg(long)]
+        title: Option<String>,
+    },
+}
+
+/// The days to work on: `all`, every day of a year (`2020/all`), a day of a year (`2020/13`),
+/// or a day of whichever year has one (`13`).
+#[derive(Debug, Clone, Copy)]
+enum Selection {
+    All,
+    Year(u32),
+    Day(Option<u32>, u32),
+}
+
+impl FromStr for Selection {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let number = |n: &str| {
+            n.parse().map_err(|_| {
+                anyhow!(
+                    "expected 'all', '<year>/all', '<year>/<day>' or a day number, got '{}'",
+                    s
+                )
+            })
+        };
+        match s.split_once('/') {
+            None if s == "all" => Ok(Selection::All),
+            None => Ok(Selection::Day(None, number(s)?)),
+            Some((year, "all")) => Ok(Selection::Year(number(year)?)),
+            Some((year, day)) => Ok(Selection::Day(Some(number(year)?), number(day)?)),
+        }
+    }
+}
+
+impl Selection {
+    fn days(&self) -> Result<Vec<Day>> {
+        let days = days::all();
+        match *self {
+            Selection::All => Ok(days),
+            Selection::Year(year) => {
+                let days: Vec<Day> = days.into_iter().filter(|d| d.year == year).collect();
+                if days.is_empty() {
+                    anyhow::bail!("no solutions for {}", year);
+                }
+                Ok(days)
+            }
+            Selection::Day(year, day) => {
+                let days: Vec<Day> = days
+                    .into_iter()
+                    .filter(|d| d.day == day && year.is_none_or(|year| d.year == year))
+                    .collect();
+                match days.as_slice() {
+                    [] => match year {
+                        Some(year) => anyhow::bail!("no solution for {} day {}", year, day),
+                        None => anyhow::bail!("no solution for day {}", day),
+                    },
+                    [_] => Ok(days),
+                    _ => {
+                        let ids: Vec<String> = days
+                            .iter()
+                            .map(|d| format!("{}/{}", d.year, d.day))
+                            .collect();
+                        anyhow::bail!("day {} is ambiguous: give one of {}", day, ids.join(", "))
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn main() -> Result<()> {
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
+
+    let cli = Cli::parse();
+    // Only a run can be traced; everything else passes the solvers' events to the logger
+    let _trace = match &cli.command {
+        Command::Run { trace, .. } => trace.start()?,
+        _ => {
+            trace::to_log()?;
+            None
+        }
+    };
+    match cli.command {
+        Command::Run {
+            day,
+            part,
+            input,
+            report,
+            cache,
+            trace: _,
+            explain,
+            profile,
+            jobs,
+            timeout,
+        } => {
+            let explain = explain.format();
+            if explain.is_some() && report.to_stdout() {
+                anyhow::bail!(
+                    "--explain and --format both write to stdout; give --format an -o file"
+                );
+            }
+            let input = input.input();
+            let cache = cache.cache();
+            let mut reporter = report.reporter()?;
+            let days = day.days()?;
+            input.check_days(days.len())?;
+            let parts = match part {
+                Some(part) => vec![part],
+                None => Part::ALL.to_vec(),
+            };
+            let jobs = jobs
+                .or_else(|| thread::available_parallelism().ok())
+                .map_or(1, NonZeroUsize::get)
+                .min(days.len());
+            let start = Instant::now();
+            let mut recorded = Ok(());
+            let results = schedule::run(
+                &days,
+                jobs,
+                |day| {
+                    let detail = Detail {
+                        explain: explain.is_some(),
+                        render: false,
+                    };
+                    solve(day, &parts, &input, &cache, timeout, detail)
+                },
+                |day, result| {
+                    if recorded.is_ok() {
+                        recorded = record(day, &parts, &input, result, &mut reporter, explain);
+                    }
+                },
+            );
+            recorded?;
+            reporter.finish()?;
+            if !matches!(day, Selection::Day(..)) {
+                summary::print(&days, &results, start.elapsed(), jobs);
+            }
+            if profile {
+                let profiles = results
+                    .iter()
+                    .flatten()
+                    .map(|solved| solved.profile.clone())
+                    .collect::<Vec<_>>();
+                profile::print(&profiles);
+            }
+            let failed = results.iter().filter(|result| result.is_err()).count();
+            if failed > 0 {
+                anyhow::bail!("{} day(s) failed", failed);
+            }
+        }
+        Command::Watch {
+            day,
+            part,
+            input,
+            cache,
+            interval,
+            timeout,
+        } => {
+            let input = input.input();
+            let days = day.days()?;
+            input.check_days(days.len())?;
+            let parts = match part {
+                Some(part) => vec![part],
+                None => Part::ALL.to_vec(),
+            };
+            let mut watcher = watch::Watcher::new(&days, parts, input, cache.cache(), timeout)?;
+            watcher.run(&mut std::io::stdout().lock(), interval)?;
+        }
+        Command::Check { day, input } => {
+            let input = input.input();
+            let days = day.days()?;
+            input.check_days(days.len())?;
+            let mut out = std::io::stdout().lock();
+            let mut failed = 0;
+            for day in &days {
+                let problems = check::check(day, &input);
+                if !matches!(&problems, Ok(problems) if problems.is_empty()) {
+                    failed += 1;
+                }
+                check::write(&mut out, day, &problems)?;
+            }
+            if failed > 0 {
+                anyhow::bail!("{} day(s) with problems in their input", failed);
+            }
+        }
+        Command::Verify {
+            day,
+            variant,
+            inputs_dir,
+        } => {
+            let answers = answers::Answers::load(&inputs_dir)?;
+            let checks = verify::verify(&day.days()?, &inputs_dir, variant.as_deref(), &answers);
+            verify::print(&checks);
+            if checks
+                .iter()
+                .any(|check| check.status() == verify::Status::Fail)
+            {
+                anyhow::bail!("verification failed");
+            }
+        }
+        Command::Gen {
+            day,
+            size,
+            seed,
+            output,
+            variant,
+            inputs_dir,
+        } => {
+            let destination = match (output, variant) {
+                (Some(path), _) => Destination::Path(path),
+                (None, Some(variant)) => Destination::Variant(inputs_dir, variant),
+                (None, None) => Destination::Stdout,
+            };
+            generate::generate(&day.days()?, size, seed, &destination)?;
+        }
+        Command::Serve {
+            day,
+            port,
+            variant,
+            inputs_dir,
+            cache,
+            jobs,
+            timeout,
+        } => {
+            let answers = answers::Answers::load(&inputs_dir)?;
+            let input = Input::Variant(inputs_dir, variant);
+            let days = day.days()?;
+            let jobs = jobs
+                .or_else(|| thread::available_parallelism().ok())
+                .map_or(1, NonZeroUsize::get)
+                .min(days.len());
+            let mut dashboard =
+                serve::Dashboard::new(days, input, answers, cache.cache(), timeout, jobs);
+            dashboard.run()?;
+            dashboard.serve(port)?;
+        }
+        Command::NewDay { day, year, title } => {
+            let year = year
+                .or_else(|| days::all().iter().map(|d| d.year).max())
+                .ok_or_else(|| anyhow!("no days yet; give the --year"))?;
+            let title = title.unwrap_or_else(|| format!("Day {}", day));
+            let name = scaffold::new_day(Path::new("."), year, day, &title)?;
+            info!("{} created {}: {}", emoji("christmas_tree"), name, title);
+        }
+    }
+    Ok(())
+}
+
+/// Load and solve the given parts of a day, timing each phase.
+///
+/// Parts with a cached answer for the input are not solved again; if every part has one, the
+/// input is not even parsed. Solving is cancelled once `timeout` has passed, for solvers which
+/// check. Parts are always solved afresh when asked for any [`Detail`] beyond the answer.
+fn solve(
+    day: &Day,
+    parts: &[Part],
+    input: &Input,
+    cache: &Cache,
+    timeout: Option<Duration>,
+    detail: Detail,
+) -> Result<Solved> {
+    info!(
+        "{} {}: {} ({})",
+        emoji("christmas_tree"),
+        day,
+        day.title,
+        input
+    );
+    let _day = info_span!("day", year = day.year, day = day.day).entered();
+    let memory = PeakMemory::start();
+    let mut profile = Profile {
+        year: day.year,
+        day: day.day,
+        title: day.title,
+        ..Default::default()
+    };
+    let (contents, duration) = timed(|| info_span!("read").in_scope(|| input.read(day)));
+    profile.load = duration;
+    let contents = contents?;
+    let hash = cache::hash(&contents);
+    let mut answers = Vec::new();
+    let mut explanations = Vec::new();
+    let mut renderings = Vec::new();
+    let mut fresh = Vec::new();
+    for &part in parts {
+        match cache.get(day, part, &hash).filter(|_| !detail.any()) {
+            Some(answer) => answers.push((part, answer, Origin::Cached)),
+            None => fresh.push(part),
+        }
+    }
+    if !fresh.is_empty() {
+        let cancel = match timeout {
+            Some(timeout) => Cancel::with_deadline(Instant::now() + timeout),
+            None => Cancel::new(),
+        };
+        let (solution, duration) =
+            timed(|| info_span!("parse").in_scope(|| input.parse(day, &contents)));
+        profile.load += duration;
+        let mut solution = solution?;
+        solution.set_cancel(cancel.clone());
+        debug!("{} {}: {:?}", emoji("christmas_tree"), "solution", solution);
+        profile.analyse = timed(|| info_span!("analyse").in_scope(|| solution.analyse())).1;
+        for part in fresh {
+            let (answer, duration) =
+                timed(|| info_span!("answer", %part).in_scope(|| solution.answer(part)));
+            profile.set_part(part, duration);
+            // A solver gives up without an answer when cancelled
+            let answer = match (timeout, cancel.is_cancelled()) {
+                (Some(timeout), true) if !answer.is_solved() => Answer::TimedOut(timeout),
+                _ => answer,
+            };
+            if let Err(e) = cache.put(day, part, &hash, &answer) {
+                warn!("{} {}: not cached: {:#}", day, part, e);
+            }
+            answers.push((part, answer, Origin::Fresh));
+            if detail.explain {
+                explanations.push((part, solution.explain(part)));
+            }
+            if let Some(rendering) = detail.render.then(|| solution.render(part)).flatten() {
+                renderings.push((part, rendering));
+            }
+        }
+        answers.sort_by_key(|(part, ..)| *part);
+    }
+    profile.peak = memory.peak();
+    Ok(Solved {
+        profile,
+        answers,
+        explanations,
+        renderings,
+    })
+}
+
+/// What to find out about each answer, beyond the answer itself.
+#[derive(Debug, Clone, Copy, Default)]
+struct Detail {
+    /// How the answer was reached.
+    explain: bool,
+    /// A picture of the puzzle once answered.
+    render: bool,
+}
+
+impl Detail {
+    fn any(&self) -> bool {
+        self.explain || self.render
+    }
+}
+
+/// Log and report the outcome of solving a day, as soon as it is known.
+fn record(
+    day: &Day,
+    parts: &[Part],
+    input: &Input,
+    result: &Result<Solved>,
+    reporter: &mut Reporter<impl Write>,
+    explain: Option<explain::Format>,
+) -> Result<()> {
+    match result {
+        Ok(solved) => {
+            for (part, answer, origin) in &solved.answers {
+                info!(
+                    "{} {} {} {} answer is {}",
+                    emoji("santa"),
+                    day,
+                    part,
+                    origin,
+                    answer
+                );
+                let duration = solved.profile.part(*part).unwrap_or_default();
+                reporter.record(&Record::answer(
+                    day,
+                    *part,
+                    input,
+                    answer.clone(),
+                    duration,
+                    *origin,
+                ))?;
+            }
+            if let Some(format) = explain {
+                let mut out = std::io::stdout().lock();
+                for (part, explanation) in &solved.explanations {
+                    explain::write(&mut out, format, day, *part, explanation.as_ref())?;
+                }
+            }
+        }
+        Err(e) => {
+            error!("{}: {:#}", day, e);
+            for part in parts {
+                reporter.record(&Record::error(day, *part, input))?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn seconds(s: &str) -> Result<Duration> {
+    Ok(Duration::try_from_secs_f64(s.parse()?)?)
+}
+
+fn emoji(name: &str) -> &'static str {
+    emojis::lookup(name)
+        .map(|emoji| emoji.as_str())
+        .unwrap_or("")
+}
@@ -0,0 +1,169 @@
+use puzzle::Part;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::time::{Duration, Instant};
+
+/// The system allocator, counting the bytes live on each thread and their high-water mark.
+pub struct Counting;
+
+#[global_allocator]
+static ALLOCATOR: Counting = Counting;
+
+thread_local! {
+    static ALLOCATED: Cell<isize> = const { Cell::new(0) };
+    static PEAK: Cell<isize> = const { Cell::new(0) };
+}
+
+fn track(delta: isize) {
+    // Ignore allocations made while the thread-locals are being torn down.
+    let _ = ALLOCATED.try_with(|allocated| {
+        let now = allocated.get() + delta;
+        allocated.set(now);
+        let _ = PEAK.try_with(|peak| peak.set(peak.get().max(now)));
+    });
+}
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            track(layout.size() as isize);
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            track(layout.size() as isize);
+        }
+        ptr
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new = System.realloc(ptr, layout, new_size);
+        if !new.is_null() {
+            track(new_size as isize - layout.size() as isize);
+        }
+        new
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        track(-(layout.size() as isize));
+    }
+}
+
+/// Measures the peak number of bytes allocated by the current thread since it was started.
+pub struct PeakMemory {
+    baseline: isize,
+}
+
+impl PeakMemory {
+    pub fn start() -> Self {
+        let baseline = ALLOCATED.with(Cell::get);
+        PEAK.with(|peak| peak.set(baseline));
+        Self { baseline }
+    }
+
+    pub fn peak(&self) -> usize {
+        (PEAK.with(Cell::get) - self.baseline).max(0) as usize
+    }
+}
+
+/// Run `f`, returning its result along with how long it took.
+pub fn timed<T>(f: impl FnOnce() -> T) -> (T, Duration) {
+    let start = Instant::now();
+    let result = f();
+    (result, start.elapsed())
+}
+
+/// Time spent in each phase of solving a day, and the most memory it held at once.
+#[derive(Debug, Clone, Default)]
+pub struct Profile {
+    pub year: u32,
+    pub day: u32,
+    pub title: &'static str,
+    pub load: Duration,
+    pub analyse: Duration,
+    pub part1: Option<Duration>,
+    pub part2: Option<Duration>,
+    pub peak: usize,
+}
+
+impl Profile {
+    pub fn set_part(&mut self, part: Part, duration: Duration) {
+        match part {
+            Part::One => self.part1 = Some(duration),
+            Part::Two => self.part2 = Some(duration),
+        }
+    }
+
+    pub fn part(&self, part: Part) -> Option<Duration> {
+        match part {
+            Part::One => self.part1,
+            Part::Two => self.part2,
+        }
+    }
+
+    pub fn total(&self) -> Duration {
+        self.load + self.analyse + self.part1.unwrap_or_default() + self.part2.unwrap_or_default()
+    }
+}
+
+pub fn duration(duration: Option<Duration>) -> String {
+    duration
+        .map(|d| format!("{:.2?}", d))
+        .unwrap_or_else(|| "-".to_string())
+}
+
+fn bytes(bytes: usize) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit + 1 < UNITS.len() {
+        value /= 1024.0;
+        unit += 1;
+    }
+    match unit {
+        0 => format!("{} B", bytes),
+        _ => format!("{:.1} {}", value, UNITS[unit]),
+    }
+}
+
+/// Print the profiles as a table, one row per day.
+pub fn print(profiles: &[Profile]) {
+    println!(
+        "{:>4}  {:>3}  {:<24}  {:>10}  {:>10}  {:>10}  {:>10}  {:>10}  {:>10}",
+        "year", "day", "title", "load", "analyse", "part1", "part2", "total", "peak"
+    );
+    for profile in profiles {
+        println!(
+            "{:>4}  {:>3}  {:<24}  {:>10}  {:>10}  {:>10}  {:>10}  {:>10}  {:>10}",
+            profile.year,
+            profile.day,
+            profile.title,
+            duration(Some(profile.load)),
+            duration(Some(profile.analyse)),
+            duration(profile.part1),
+            duration(profile.part2),
+            duration(Some(profile.total())),
+            bytes(profile.peak)
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn peak_memory() {
+        let memory = PeakMemory::start();
+        let buffer = vec![0u8; 4096];
+        drop(buffer);
+        assert!(memory.peak() >= 4096);
+        assert_eq!(bytes(512), "512 B");
+        assert_eq!(bytes(3 << 20), "3.0 MiB");
+    }
+}
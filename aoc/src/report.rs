@@ -0,0 +1,241 @@
+use crate::cache::Origin;
+use anyhow::Result;
+use clap::{Args, ValueEnum};
+use puzzle::{Answer, Day, Part};
+use serde::{Serialize, Serializer};
+use std::fmt::Display;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Command line options selecting where structured results are written.
+#[derive(Debug, Clone, Args)]
+pub struct ReportArgs {
+    /// Also write a record per answer in this format
+    #[arg(long, value_enum)]
+    format: Option<Format>,
+    /// Write the records to this file rather than stdout
+    #[arg(long, short, requires = "format")]
+    output: Option<PathBuf>,
+}
+
+impl ReportArgs {
+    pub fn reporter(&self) -> Result<Reporter<Box<dyn Write>>> {
+        let format = match self.format {
+            Some(format) => format,
+            None => return Ok(Reporter::Discard),
+        };
+        let writer: Box<dyn Write> = match &self.output {
+            Some(path) => Box::new(BufWriter::new(File::create(path)?)),
+            None => Box::new(io::stdout()),
+        };
+        Ok(Reporter::new(format, writer))
+    }
+
+    /// Whether records are written to stdout.
+    pub fn to_stdout(&self) -> bool {
+        self.format.is_some() && self.output.is_none()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+    /// One JSON object per line
+    Json,
+    /// Comma separated, with a header row
+    Csv,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Status {
+    /// The part was answered.
+    Ok,
+    /// The solution has no answer for the part.
+    Unsolved,
+    /// The solution gave up on the part when its time ran out.
+    Timeout,
+    /// The input could not be loaded.
+    Error,
+}
+
+/// The outcome of one part of one day, as written by a [`Reporter`].
+#[derive(Debug, Clone, Serialize)]
+pub struct Record {
+    pub year: u32,
+    pub day: u32,
+    #[serde(serialize_with = "display")]
+    pub part: Part,
+    pub input: String,
+    /// Integers as numbers where possible, other answers as strings, and nothing if unsolved.
+    #[serde(serialize_with = "answer")]
+    pub answer: Answer,
+    /// Seconds spent producing the answer.
+    pub duration: f64,
+    pub status: Status,
+    /// Whether the answer was worked out by this run or served from the cache.
+    pub origin: Origin,
+}
+
+impl Record {
+    pub fn answer(
+        day: &Day,
+        part: Part,
+        input: &impl Display,
+        answer: Answer,
+        duration: Duration,
+        origin: Origin,
+    ) -> Self {
+        Self {
+            year: day.year,
+            day: day.day,
+            part,
+            input: input.to_string(),
+            status: match answer {
+                Answer::Unsolved(_) => Status::Unsolved,
+                Answer::TimedOut(_) => Status::Timeout,
+                _ => Status::Ok,
+            },
+            answer,
+            duration: duration.as_secs_f64(),
+            origin,
+        }
+    }
+
+    pub fn error(day: &Day, part: Part, input: &impl Display) -> Self {
+        Self {
+            year: day.year,
+            day: day.day,
+            part,
+            input: input.to_string(),
+            answer: Answer::Unsolved("the input could not be loaded".to_string()),
+            duration: 0.0,
+            status: Status::Error,
+            origin: Origin::Fresh,
+        }
+    }
+}
+
+fn answer<S: Serializer>(answer: &Answer, serializer: S) -> Result<S::Ok, S::Error> {
+    match answer {
+        Answer::Integer(value) => serializer.serialize_i64(*value),
+        Answer::Unsolved(_) | Answer::TimedOut(_) => serializer.serialize_none(),
+        answer => serializer.collect_str(answer),
+    }
+}
+
+fn display<T: Display, S: Serializer>(value: &T, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(value)
+}
+
+/// Writes [`Record`]s in the requested format, or nowhere.
+pub enum Reporter<W: Write> {
+    Discard,
+    Json(W),
+    Csv(Box<csv::Writer<W>>),
+}
+
+impl<W: Write> Reporter<W> {
+    pub fn new(format: Format, writer: W) -> Self {
+        match format {
+            Format::Json => Reporter::Json(writer),
+            Format::Csv => Reporter::Csv(Box::new(csv::Writer::from_writer(writer))),
+        }
+    }
+
+    pub fn record(&mut self, record: &Record) -> Result<()> {
+        match self {
+            Reporter::Discard => {}
+            Reporter::Json(writer) => {
+                serde_json::to_writer(&mut *writer, record)?;
+                writeln!(writer)?;
+            }
+            Reporter::Csv(writer) => writer.serialize(record)?,
+        }
+        Ok(())
+    }
+
+    pub fn finish(self) -> Result<()> {
+        match self {
+            Reporter::Discard => {}
+            Reporter::Json(mut writer) => writer.flush()?,
+            Reporter::Csv(mut writer) => writer.flush()?,
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(format: Format) -> String {
+        let mut buffer = Vec::new();
+        let mut reporter = Reporter::new(format, &mut buffer);
+        let day5 = Day::of::<day5::Solution>();
+        let day6 = Day::of::<day6::Solution>();
+        let day7 = Day::of::<day7::Solution>();
+        let records = [
+            Record::answer(
+                &day5,
+                Part::One,
+                &"small",
+                Answer::Integer(820),
+                Duration::from_millis(1500),
+                Origin::Fresh,
+            ),
+            Record::answer(
+                &day5,
+                Part::Two,
+                &"small",
+                None::<i64>.into(),
+                Duration::ZERO,
+                Origin::Fresh,
+            ),
+            Record::error(&day6, Part::One, &"full"),
+            Record::answer(
+                &day6,
+                Part::Two,
+                &"full",
+                Answer::TimedOut(Duration::from_secs(2)),
+                Duration::from_secs(2),
+                Origin::Fresh,
+            ),
+            Record::answer(
+                &day7,
+                Part::One,
+                &"full",
+                "abc".into(),
+                Duration::ZERO,
+                Origin::Cached,
+            ),
+        ];
+        for record in &records {
+            reporter.record(record).unwrap();
+        }
+        reporter.finish().unwrap();
+        String::from_utf8(buffer).unwrap()
+    }
+
+    #[test]
+    fn formats() {
+        assert_eq!(
+            render(Format::Csv),
+            "year,day,part,input,answer,duration,status,origin\n\
+             2020,5,part1,small,820,1.5,ok,fresh\n\
+             2020,5,part2,small,,0.0,unsolved,fresh\n\
+             2020,6,part1,full,,0.0,error,fresh\n\
+             2020,6,part2,full,,2.0,timeout,fresh\n\
+             2020,7,part1,full,abc,0.0,ok,cached\n"
+        );
+        assert_eq!(
+            render(Format::Json),
+            "{\"year\":2020,\"day\":5,\"part\":\"part1\",\"input\":\"small\",\"answer\":820,\"duration\":1.5,\"status\":\"ok\",\"origin\":\"fresh\"}\n\
+             {\"year\":2020,\"day\":5,\"part\":\"part2\",\"input\":\"small\",\"answer\":null,\"duration\":0.0,\"status\":\"unsolved\",\"origin\":\"fresh\"}\n\
+             {\"year\":2020,\"day\":6,\"part\":\"part1\",\"input\":\"full\",\"answer\":null,\"duration\":0.0,\"status\":\"error\",\"origin\":\"fresh\"}\n\
+             {\"year\":2020,\"day\":6,\"part\":\"part2\",\"input\":\"full\",\"answer\":null,\"duration\":2.0,\"status\":\"timeout\",\"origin\":\"fresh\"}\n\
+             {\"year\":2020,\"day\":7,\"part\":\"part1\",\"input\":\"full\",\"answer\":\"abc\",\"duration\":0.0,\"status\":\"ok\",\"origin\":\"cached\"}\n"
+        );
+    }
+}
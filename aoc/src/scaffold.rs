@@ -0,0 +1,287 @@
+use anyhow::{bail, Context, Result};
+use std::fs;
+use std::path::Path;
+
+/// The year whose days were written before there were any others, so keep their original
+/// crate names at the workspace root.
+const FIRST_YEAR: u32 = 2020;
+
+/// Create a day from the `template` crate and wire it into the workspace, runner and benches,
+/// returning the name of its crate.
+///
+/// Days of the first year are `day<N>` crates at the workspace root; later years' days are
+/// `aoc<year>-day<N>` crates in `<year>/day<N>`, so that no two years clash. Paths are relative
+/// to `root`, the workspace directory.
+pub fn new_day(root: &Path, year: u32, day: u32, title: &str) -> Result<String> {
+    if !(1..=25).contains(&day) {
+        bail!("day must be between 1 and 25, got {}", day);
+    }
+    let id = (year, day);
+    let dir = root.join(directory(id));
+    if dir.exists() {
+        bail!("'{}' already exists", dir.display());
+    }
+    // From the new crate back up to the workspace root
+    let up = "../".repeat(directory(id).split('/').count());
+
+    let manifest = read(&root.join("template/Cargo.toml"))?;
+    let manifest = replace(
+        &manifest,
+        "name = \"template\"",
+        &format!("name = \"{}\"", package(id)),
+    )?;
+    let manifest = manifest.replace("path = \"../", &format!("path = \"{}", up));
+    let source = read(&root.join("template/src/lib.rs"))?;
+    let source = replace(
+        &source,
+        "const YEAR: u32 = 0;",
+        &format!("const YEAR: u32 = {};", year),
+    )?;
+    let source = replace(
+        &source,
+        "const DAY: u32 = 0;",
+        &format!("const DAY: u32 = {};", day),
+    )?;
+    let source = replace(
+        &source,
+        "const TITLE: &'static str = \"Template\";",
+        &format!("const TITLE: &'static str = {:?};", title),
+    )?;
+
+    fs::create_dir_all(dir.join("src"))?;
+    fs::create_dir_all(dir.join("tests"))?;
+    write(&dir.join("Cargo.toml"), &manifest)?;
+    write(&dir.join("src/lib.rs"), &source)?;
+    write(&dir.join("tests/example.rs"), &example(id, &up))?;
+
+    let fixture = root.join(format!("inputs/{}/input.day{}.small", year, day));
+    if !fixture.exists() {
+        fs::create_dir_all(root.join(format!("inputs/{}", year)))?;
+        write(&fixture, "")?;
+    }
+
+    update(&root.join("Cargo.toml"), |text| add_member(text, id))?;
+    for manifest in ["aoc/Cargo.toml", "benches/Cargo.toml"] {
+        update(&root.join(manifest), |text| {
+            insert_sorted(text, id, &dependency(id), |line| {
+                let id = id_of(line.split_once(' ')?.0)?;
+                (line == dependency(id)).then_some(id)
+            })
+        })?;
+    }
+    update(&root.join("aoc/src/days.rs"), |text| {
+        insert_sorted(
+            text,
+            id,
+            &format!("        Day::of::<{}::Solution>(),", library(id)),
+            |line| library_of(line, "        Day::of::<", "::Solution>(),"),
+        )
+    })?;
+    update(&root.join("benches/benches/days.rs"), |text| {
+        insert_sorted(
+            text,
+            id,
+            &format!("    bench::<{}::Solution>(c);", library(id)),
+            |line| library_of(line, "    bench::<", "::Solution>(c);"),
+        )
+    })?;
+    Ok(package(id))
+}
+
+/// A day's year and number.
+type Id = (u32, u32);
+
+fn package((year, day): Id) -> String {
+    match year {
+        FIRST_YEAR => format!("day{}", day),
+        _ => format!("aoc{}-day{}", year, day),
+    }
+}
+
+/// The name of a day's crate in code.
+fn library(id: Id) -> String {
+    package(id).replace('-', "_")
+}
+
+fn directory((year, day): Id) -> String {
+    match year {
+        FIRST_YEAR => format!("day{}", day),
+        _ => format!("{}/day{}", year, day),
+    }
+}
+
+/// The day named by a crate's package, library or directory name.
+fn id_of(name: &str) -> Option<Id> {
+    let (year, day) = match name.strip_prefix("aoc") {
+        Some(rest) => rest.split_once(['-', '_'])?,
+        None => name.split_once('/').unwrap_or(("", name)),
+    };
+    let year = match year {
+        "" => FIRST_YEAR,
+        year => year.parse().ok()?,
+    };
+    Some((year, day.strip_prefix("day")?.parse().ok()?))
+}
+
+/// The day of `line`, if it is `prefix<library>suffix`.
+fn library_of(line: &str, prefix: &str, suffix: &str) -> Option<Id> {
+    let name = line.strip_prefix(prefix)?.strip_suffix(suffix)?;
+    id_of(name).filter(|&id| library(id) == name)
+}
+
+fn read(path: &Path) -> Result<String> {
+    fs::read_to_string(path).with_context(|| format!("reading '{}'", path.display()))
+}
+
+fn write(path: &Path, text: &str) -> Result<()> {
+    fs::write(path, text).with_context(|| format!("writing '{}'", path.display()))
+}
+
+fn update(path: &Path, edit: impl FnOnce(&str) -> Result<String>) -> Result<()> {
+    let text = read(path)?;
+    let text = edit(&text).with_context(|| format!("updating '{}'", path.display()))?;
+    write(path, &text)
+}
+
+fn replace(text: &str, from: &str, to: &str) -> Result<String> {
+    if !text.contains(from) {
+        bail!("template no longer contains '{}'", from);
+    }
+    Ok(text.replacen(from, to, 1))
+}
+
+/// The integration test skeleton for a new day, asserting its answers on the example input.
+///
+/// `up` leads from the crate to the workspace root.
+fn example(id: Id, up: &str) -> String {
+    format!(
+        r#"use {0}::Solution;
+use puzzle::Puzzle;
+
+#[test]
+fn example() {{
+    let mut solution =
+        Solution::parse(include_str!("../{1}inputs/{2}/input.day{3}.small")).unwrap();
+    solution.analyse();
+    assert!(!solution.answer_part1().is_solved());
+    assert!(!solution.answer_part2().is_solved());
+}}
+"#,
+        library(id),
+        up,
+        id.0,
+        id.1
+    )
+}
+
+fn dependency(id: Id) -> String {
+    format!("{} = {{ path = \"../{}\" }}", package(id), directory(id))
+}
+
+/// Insert `new` among the consecutive lines recognised by `id_of`, keeping them in calendar
+/// order.
+fn insert_sorted(
+    text: &str,
+    id: Id,
+    new: &str,
+    id_of: impl Fn(&str) -> Option<Id>,
+) -> Result<String> {
+    let mut lines: Vec<&str> = text.lines().collect();
+    let days: Vec<(usize, Id)> = lines
+        .iter()
+        .enumerate()
+        .filter_map(|(index, line)| id_of(line).map(|d| (index, d)))
+        .collect();
+    if days.iter().any(|&(_, d)| d == id) {
+        bail!("{} day {} is already listed", id.0, id.1);
+    }
+    let index = match days.iter().rev().find(|&&(_, d)| d < id) {
+        Some(&(index, _)) => index + 1,
+        None => match days.first() {
+            Some(&(index, _)) => index,
+            None => bail!("no days are listed"),
+        },
+    };
+    lines.insert(index, new);
+    Ok(lines.join("\n") + "\n")
+}
+
+/// Add a day's directory to the workspace members, whose last entry has no trailing comma.
+fn add_member(manifest: &str, id: Id) -> Result<String> {
+    let member = |line: &str| {
+        let name = line
+            .trim_end_matches(',')
+            .strip_prefix("    \"")?
+            .strip_suffix('"')?;
+        id_of(name).filter(|&id| directory(id) == name)
+    };
+    let text = insert_sorted(manifest, id, &format!("    \"{}\",", directory(id)), member)?;
+    let mut lines: Vec<String> = text.lines().map(String::from).collect();
+    for index in 0..lines.len() {
+        if member(&lines[index]).is_none() {
+            continue;
+        }
+        let last = lines.get(index + 1).is_none_or(|next| next.trim() == "]");
+        let line = lines[index].trim_end_matches(',').to_string();
+        lines[index] = if last { line } else { line + "," };
+    }
+    Ok(lines.join("\n") + "\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wiring() {
+        let manifest =
+            "[workspace]\n\nmembers = [\n    \"aoc\",\n    \"day9\",\n    \"day11\"\n]\n";
+        assert_eq!(
+            add_member(manifest, (2020, 10)).unwrap(),
+            "[workspace]\n\nmembers = [\n    \"aoc\",\n    \"day9\",\n    \"day10\",\n    \"day11\"\n]\n"
+        );
+        assert_eq!(
+            add_member(manifest, (2020, 20)).unwrap(),
+            "[workspace]\n\nmembers = [\n    \"aoc\",\n    \"day9\",\n    \"day11\",\n    \"day20\"\n]\n"
+        );
+        assert_eq!(
+            add_member(manifest, (2021, 1)).unwrap(),
+            "[workspace]\n\nmembers = [\n    \"aoc\",\n    \"day9\",\n    \"day11\",\n    \"2021/day1\"\n]\n"
+        );
+        assert!(add_member(manifest, (2020, 9)).is_err());
+
+        let registry = "vec![\n        Day::of::<day2::Solution>(),\n        Day::of::<aoc2021_day1::Solution>(),\n    ]\n";
+        let id_of = |line: &str| library_of(line, "        Day::of::<", "::Solution>(),");
+        assert_eq!(
+            insert_sorted(registry, (2020, 1), "        Day::of::<day1::Solution>(),", id_of).unwrap(),
+            "vec![\n        Day::of::<day1::Solution>(),\n        Day::of::<day2::Solution>(),\n        Day::of::<aoc2021_day1::Solution>(),\n    ]\n"
+        );
+        assert!(insert_sorted(registry, (2021, 1), "", id_of).is_err());
+    }
+
+    #[test]
+    fn names() {
+        assert_eq!(dependency((2020, 13)), "day13 = { path = \"../day13\" }");
+        assert_eq!(
+            dependency((2021, 1)),
+            "aoc2021-day1 = { path = \"../2021/day1\" }"
+        );
+        for id in [(2020, 13), (2021, 1)] {
+            for name in [package(id), library(id), directory(id)] {
+                assert_eq!(id_of(&name), Some(id), "{}", name);
+            }
+        }
+        assert_eq!(id_of("aoc"), None);
+        assert_eq!(id_of("puzzle"), None);
+    }
+
+    #[test]
+    fn example_fits_rustfmt() {
+        for id in [(2020, 25), (2021, 25)] {
+            let up = "../".repeat(directory(id).split('/').count());
+            for line in example(id, &up).lines() {
+                assert!(line.len() <= 100, "{}", line);
+            }
+        }
+    }
+}
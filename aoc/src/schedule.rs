@@ -0,0 +1,111 @@
+use anyhow::{anyhow, Result};
+use std::any::Any;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::thread;
+
+/// Run `task` on every item using a pool of `jobs` worker threads, each taking the next item
+/// as soon as it is free.
+///
+/// Results are handed to `finished` on the calling thread in the order they complete, then
+/// returned in the order of `items`. A task which panics fails, without taking the others down.
+pub fn run<T, R>(
+    items: &[T],
+    jobs: usize,
+    task: impl Fn(&T) -> Result<R> + Sync,
+    mut finished: impl FnMut(&T, &Result<R>),
+) -> Vec<Result<R>>
+where
+    T: Sync,
+    R: Send,
+{
+    let next = AtomicUsize::new(0);
+    let (sender, receiver) = mpsc::channel();
+    let mut results: Vec<Option<Result<R>>> = items.iter().map(|_| None).collect();
+    thread::scope(|scope| {
+        for _ in 0..jobs.clamp(1, items.len().max(1)) {
+            let sender = sender.clone();
+            let (next, task) = (&next, &task);
+            scope.spawn(move || loop {
+                let index = next.fetch_add(1, Ordering::Relaxed);
+                let Some(item) = items.get(index) else {
+                    break;
+                };
+                let result = catch(|| task(item));
+                if sender.send((index, result)).is_err() {
+                    break;
+                }
+            });
+        }
+        drop(sender);
+        for (index, result) in receiver {
+            finished(&items[index], &result);
+            results[index] = Some(result);
+        }
+    });
+    results
+        .into_iter()
+        .map(|result| result.expect("every item is run"))
+        .collect()
+}
+
+/// Run `task`, failing rather than unwinding further if it panics.
+pub fn catch<R>(task: impl FnOnce() -> Result<R>) -> Result<R> {
+    panic::catch_unwind(AssertUnwindSafe(task))
+        .unwrap_or_else(|payload| Err(anyhow!("panicked: {}", message(&*payload))))
+}
+
+/// The message a panic was raised with, if it was given one.
+fn message(payload: &(dyn Any + Send)) -> &str {
+    match payload.downcast_ref::<&str>() {
+        Some(message) => message,
+        None => payload
+            .downcast_ref::<String>()
+            .map_or("no message", String::as_str),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn ordered_results() {
+        let items = [30, 0, 20, 10];
+        let mut finished = Vec::new();
+        let results = run(
+            &items,
+            4,
+            |&millis| {
+                thread::sleep(Duration::from_millis(millis));
+                Ok(millis * 2)
+            },
+            |&item, result| finished.push((item, *result.as_ref().unwrap())),
+        );
+        let results: Vec<_> = results.into_iter().map(Result::unwrap).collect();
+        assert_eq!(results, vec![60, 0, 40, 20]);
+        finished.sort();
+        assert_eq!(finished, vec![(0, 0), (10, 20), (20, 40), (30, 60)]);
+    }
+
+    #[test]
+    fn panics_fail_their_task() {
+        let results = run(
+            &[1, 0, 2],
+            2,
+            |&divisor| match divisor {
+                0 => panic!("divided by zero"),
+                divisor => Ok(6 / divisor),
+            },
+            |_, _| {},
+        );
+        assert_eq!(results[0].as_ref().unwrap(), &6);
+        assert_eq!(
+            results[1].as_ref().unwrap_err().to_string(),
+            "panicked: divided by zero"
+        );
+        assert_eq!(results[2].as_ref().unwrap(), &3);
+    }
+}
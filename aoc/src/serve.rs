@@ -0,0 +1,563 @@
+use crate::answers::Answers;
+use crate::cache::Cache;
+use crate::explain;
+use crate::input::Input;
+use crate::profile::duration;
+use crate::report::{Record, Reporter};
+use crate::summary::Solved;
+use crate::verify::{Check, Outcome, Status};
+use crate::{record, schedule, solve, Detail};
+use anyhow::{anyhow, Result};
+use log::{info, warn};
+use puzzle::{Day, Explanation, Part};
+use serde::Serialize;
+use std::fmt::Write;
+use std::io;
+use std::time::Duration;
+use tiny_http::{Header, Method, Response, Server};
+
+/// Everything served about one day: its answers, whether they match those recorded, how they
+/// were reached and pictures of the puzzle.
+#[derive(Debug, Serialize)]
+pub struct DayResult {
+    year: u32,
+    day: u32,
+    title: &'static str,
+    /// Why the day could not be solved, if it could not.
+    error: Option<String>,
+    /// Seconds spent solving the day.
+    duration: f64,
+    parts: Vec<PartResult>,
+    /// Whether the parts have been explained and drawn, which is only done once asked for.
+    #[serde(skip)]
+    detailed: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct PartResult {
+    #[serde(flatten)]
+    record: Record,
+    /// The answer checked against the one recorded for the input variant.
+    verified: Status,
+    #[serde(serialize_with = "explain::json")]
+    explanation: Option<Explanation>,
+    rendering: Option<String>,
+}
+
+impl DayResult {
+    pub fn new(day: &Day, result: &Result<Solved>, input: &Input, answers: &Answers) -> Self {
+        let variant = input.to_string();
+        let check = |part, outcome| {
+            Check {
+                year: day.year,
+                day: day.day,
+                variant: variant.clone(),
+                part,
+                expected: answers.get(day, &variant, part),
+                outcome,
+            }
+            .status()
+        };
+        match result {
+            Ok(solved) => Self {
+                year: day.year,
+                day: day.day,
+                title: day.title,
+                error: None,
+                duration: solved.profile.total().as_secs_f64(),
+                parts: solved
+                    .answers
+                    .iter()
+                    .map(|(part, answer, origin)| PartResult {
+                        record: Record::answer(
+                            day,
+                            *part,
+                            input,
+                            answer.clone(),
+                            solved.profile.part(*part).unwrap_or_default(),
+                            *origin,
+                        ),
+                        verified: check(*part, Outcome::Answer(answer.clone())),
+                        explanation: solved
+                            .explanations
+                            .iter()
+                            .find(|(p, _)| p == part)
+                            .and_then(|(_, explanation)| explanation.clone()),
+                        rendering: solved
+                            .renderings
+                            .iter()
+                            .find(|(p, _)| p == part)
+                            .map(|(_, rendering)| rendering.clone()),
+                    })
+                    .collect(),
+                detailed: false,
+            },
+            Err(e) => {
+                let outcome = match input.path(day) {
+                    Some(path) if !path.exists() => Outcome::NoInput,
+                    _ => Outcome::Error(format!("{:#}", e)),
+                };
+                Self {
+                    year: day.year,
+                    day: day.day,
+                    title: day.title,
+                    error: Some(format!("{:#}", e)),
+                    duration: 0.0,
+                    parts: Part::ALL
+                        .into_iter()
+                        .map(|part| PartResult {
+                            record: Record::error(day, part, input),
+                            verified: check(part, outcome.clone()),
+                            explanation: None,
+                            rendering: None,
+                        })
+                        .collect(),
+                    detailed: false,
+                }
+            }
+        }
+    }
+
+    fn part(&self, part: Part) -> Option<&PartResult> {
+        self.parts.iter().find(|result| result.record.part == part)
+    }
+}
+
+/// Solves the days against one input variant, and serves the results over HTTP.
+pub struct Dashboard {
+    days: Vec<Day>,
+    input: Input,
+    answers: Answers,
+    cache: Cache,
+    timeout: Option<Duration>,
+    jobs: usize,
+    results: Vec<DayResult>,
+}
+
+impl Dashboard {
+    /// A dashboard with nothing solved yet.
+    pub fn new(
+        days: Vec<Day>,
+        input: Input,
+        answers: Answers,
+        cache: Cache,
+        timeout: Option<Duration>,
+        jobs: usize,
+    ) -> Self {
+        Self {
+            days,
+            input,
+            answers,
+            cache,
+            timeout,
+            jobs,
+            results: Vec::new(),
+        }
+    }
+
+    /// Solve every day, serving answers from the cache where it can, replacing the results
+    /// served. Days are only explained and drawn once their page is asked for.
+    pub fn run(&mut self) -> Result<()> {
+        let mut recorded = Ok(());
+        let results = schedule::run(
+            &self.days,
+            self.jobs,
+            |day| {
+                solve(
+                    day,
+                    &Part::ALL,
+                    &self.input,
+                    &self.cache,
+                    self.timeout,
+                    Detail::default(),
+                )
+            },
+            |day, result| {
+                if recorded.is_ok() {
+                    let mut discard = Reporter::<io::Sink>::Discard;
+                    recorded = record(day, &Part::ALL, &self.input, result, &mut discard, None);
+                }
+            },
+        );
+        recorded?;
+        self.results = self
+            .days
+            .iter()
+            .zip(&results)
+            .map(|(day, result)| DayResult::new(day, result, &self.input, &self.answers))
+            .collect();
+        Ok(())
+    }
+
+    /// Solve a day afresh with its explanations and renderings, which cannot be cached, the
+    /// first time its page is asked for since the last run.
+    fn detail(&mut self, year: u32, n: u32) -> Result<()> {
+        let Some(index) = self
+            .results
+            .iter()
+            .position(|result| (result.year, result.day) == (year, n) && !result.detailed)
+        else {
+            return Ok(());
+        };
+        let day = &self.days[index];
+        let detail = Detail {
+            explain: true,
+            render: true,
+        };
+        let result = solve(
+            day,
+            &Part::ALL,
+            &self.input,
+            &self.cache,
+            self.timeout,
+            detail,
+        );
+        let mut discard = Reporter::<io::Sink>::Discard;
+        record(day, &Part::ALL, &self.input, &result, &mut discard, None)?;
+        self.results[index] = DayResult {
+            detailed: true,
+            ..DayResult::new(day, &result, &self.input, &self.answers)
+        };
+        Ok(())
+    }
+
+    /// Serve the dashboard on localhost until the process is stopped.
+    pub fn serve(mut self, port: u16) -> Result<()> {
+        let server = Server::http(("127.0.0.1", port)).map_err(|e| anyhow!(e))?;
+        info!("serving results at http://{}/", server.server_addr());
+        for request in server.incoming_requests() {
+            let route = Route::of(request.method(), request.url());
+            let prepared = match route {
+                Route::Run { .. } => self.run(),
+                Route::Day(year, n) | Route::DayResults(year, n) => self.detail(year, n),
+                _ => Ok(()),
+            };
+            if let Err(e) = prepared {
+                warn!("solving: {:#}", e);
+            }
+            let page = page(&self.results, &self.input, route);
+            let mut response = Response::from_string(page.body)
+                .with_status_code(page.status)
+                .with_header(header("Content-Type", page.content_type));
+            if let Some(location) = page.location {
+                response.add_header(header("Location", location));
+            }
+            if let Err(e) = request.respond(response) {
+                warn!("responding: {}", e);
+            }
+        }
+        Ok(())
+    }
+}
+
+fn header(name: &str, value: &str) -> Header {
+    Header::from_bytes(name, value).expect("headers are valid ASCII")
+}
+
+/// What a request asks for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Route {
+    /// The table of every day's answers.
+    Index,
+    /// A day's answers, explanations and renderings, by year and day.
+    Day(u32, u32),
+    Results,
+    DayResults(u32, u32),
+    /// Solve every day again, then show the results as a page or as JSON.
+    Run {
+        json: bool,
+    },
+    NotFound,
+}
+
+impl Route {
+    fn of(method: &Method, url: &str) -> Self {
+        let path = url.split('?').next().unwrap_or_default();
+        let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+        let day = |year: &str, day: &str| Some((year.parse().ok()?, day.parse().ok()?));
+        match (method, segments.as_slice()) {
+            (Method::Get, []) => Route::Index,
+            (Method::Get, ["day", year, n]) => match day(year, n) {
+                Some((year, n)) => Route::Day(year, n),
+                None => Route::NotFound,
+            },
+            (Method::Get, ["api", "results"]) => Route::Results,
+            (Method::Get, ["api", "day", year, n]) => match day(year, n) {
+                Some((year, n)) => Route::DayResults(year, n),
+                None => Route::NotFound,
+            },
+            (Method::Post, ["run"]) => Route::Run { json: false },
+            (Method::Post, ["api", "run"]) => Route::Run { json: true },
+            _ => Route::NotFound,
+        }
+    }
+}
+
+/// A response, before it is sent.
+#[derive(Debug)]
+struct Page {
+    status: u16,
+    content_type: &'static str,
+    body: String,
+    /// Where to go instead, for redirects.
+    location: Option<&'static str>,
+}
+
+impl Page {
+    fn html(title: &str, body: &str) -> Self {
+        Self {
+            status: 200,
+            content_type: "text/html; charset=utf-8",
+            body: format!(
+                "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n\
+                 <style>{}</style>\n</head>\n<body>\n{}</body>\n</html>\n",
+                escape(title),
+                STYLE,
+                body
+            ),
+            location: None,
+        }
+    }
+
+    fn json(value: &impl Serialize) -> Self {
+        match serde_json::to_string(value) {
+            Ok(body) => Self {
+                status: 200,
+                content_type: "application/json",
+                body,
+                location: None,
+            },
+            Err(e) => Self::text(500, &e.to_string()),
+        }
+    }
+
+    fn text(status: u16, body: &str) -> Self {
+        Self {
+            status,
+            content_type: "text/plain; charset=utf-8",
+            body: format!("{}\n", body),
+            location: None,
+        }
+    }
+
+    fn redirect(location: &'static str) -> Self {
+        Self {
+            status: 303,
+            location: Some(location),
+            ..Self::text(303, "see other")
+        }
+    }
+}
+
+const STYLE: &str = "body { font-family: sans-serif; margin: 2em; } \
+    table { border-collapse: collapse; } \
+    th, td { padding: 0.2em 0.8em; text-align: left; border-bottom: 1px solid #ccc; } \
+    td.number { text-align: right; } \
+    .pass { color: #080; } .fail { color: #c00; } .missing { color: #888; } \
+    pre { background: #f4f4f4; padding: 0.5em; line-height: 1.1; }";
+
+fn page(results: &[DayResult], input: &Input, route: Route) -> Page {
+    let find = |year: u32, n: u32| {
+        results
+            .iter()
+            .find(|result| (result.year, result.day) == (year, n))
+    };
+    match route {
+        Route::Index => Page::html("Advent of Code", &index(results, input)),
+        Route::Day(year, n) => match find(year, n) {
+            Some(result) => Page::html(&format!("{} day {}", year, n), &day(result, input)),
+            None => Page::text(404, "no such day"),
+        },
+        Route::Results | Route::Run { json: true } => Page::json(&results),
+        Route::DayResults(year, n) => match find(year, n) {
+            Some(result) => Page::json(result),
+            None => Page::text(404, "no such day"),
+        },
+        Route::Run { json: false } => Page::redirect("/"),
+        Route::NotFound => Page::text(404, "not found"),
+    }
+}
+
+fn index(results: &[DayResult], input: &Input) -> String {
+    let mut html = String::new();
+    let _ = writeln!(html, "<h1>Advent of Code</h1>");
+    let _ = writeln!(
+        html,
+        "<form method=\"post\" action=\"/run\">Answers for the <b>{}</b> inputs \
+         <button>Run again</button></form>",
+        escape(&input.to_string())
+    );
+    let _ = writeln!(
+        html,
+        "<table>\n<tr><th>year</th><th>day</th><th>title</th><th>part1</th><th>part2</th><th>time</th></tr>"
+    );
+    for result in results {
+        let _ = write!(
+            html,
+            "<tr><td class=\"number\">{0}</td><td class=\"number\">{1}</td>\
+             <td><a href=\"/day/{0}/{1}\">{2}</a></td>",
+            result.year,
+            result.day,
+            escape(result.title)
+        );
+        for part in Part::ALL {
+            let _ = write!(html, "<td>{}</td>", answer(result, part));
+        }
+        let time = Duration::from_secs_f64(result.duration);
+        let _ = writeln!(
+            html,
+            "<td class=\"number\">{}</td></tr>",
+            duration(result.error.is_none().then_some(time))
+        );
+    }
+    let _ = writeln!(html, "</table>");
+    html
+}
+
+fn day(result: &DayResult, input: &Input) -> String {
+    let mut html = String::new();
+    let _ = writeln!(
+        html,
+        "<p><a href=\"/\">All days</a></p>\n<h1>{} day {}: {}</h1>\n<p>{} input</p>",
+        result.year,
+        result.day,
+        escape(result.title),
+        escape(&input.to_string())
+    );
+    if let Some(error) = &result.error {
+        let _ = writeln!(html, "<p class=\"fail\">{}</p>", escape(error));
+    }
+    for part in &result.parts {
+        let _ = writeln!(
+            html,
+            "<h2>{}</h2>\n<p>{} ({}, {})</p>",
+            part.record.part,
+            answer(result, part.record.part),
+            part.record.origin,
+            duration(Some(Duration::from_secs_f64(part.record.duration)))
+        );
+        if let Some(explanation) = &part.explanation {
+            let _ = writeln!(html, "<pre>{}</pre>", escape(&explanation.to_string()));
+        }
+        if let Some(rendering) = &part.rendering {
+            let _ = writeln!(html, "<pre>{}</pre>", escape(rendering));
+        }
+    }
+    html
+}
+
+/// A part's answer and whether it matches the recorded answer, as HTML.
+fn answer(result: &DayResult, part: Part) -> String {
+    let Some(part) = result.part(part) else {
+        return "-".to_string();
+    };
+    let answer = match &result.error {
+        Some(_) => "error".to_string(),
+        None => part.record.answer.to_string(),
+    };
+    let status = part.verified.to_string().to_lowercase();
+    format!(
+        "{} <span class=\"{1}\">({1})</span>",
+        escape(&answer),
+        status
+    )
+}
+
+fn escape(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            '&' => "&amp;".to_string(),
+            '<' => "&lt;".to_string(),
+            '>' => "&gt;".to_string(),
+            '"' => "&quot;".to_string(),
+            c => c.to_string(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::Origin;
+    use crate::profile::Profile;
+    use puzzle::Answer;
+    use std::path::PathBuf;
+
+    fn results() -> Vec<DayResult> {
+        let input = Input::Variant(PathBuf::from("../inputs"), "small".to_string());
+        let answers = Answers::load(&PathBuf::from("../inputs")).unwrap();
+        let day3 = Day::of::<day3::Solution>();
+        let solved = Solved {
+            profile: Profile::default(),
+            answers: vec![
+                (Part::One, Answer::Integer(7), Origin::Fresh),
+                (Part::Two, Answer::Integer(1), Origin::Fresh),
+            ],
+            explanations: vec![(Part::One, None), (Part::Two, None)],
+            renderings: vec![(Part::One, "O.#\n.X<\n".to_string())],
+        };
+        vec![
+            DayResult::new(&day3, &Ok(solved), &input, &answers),
+            DayResult::new(
+                &Day::of::<day4::Solution>(),
+                &Err(anyhow!("bad <input>")),
+                &input,
+                &answers,
+            ),
+        ]
+    }
+
+    #[test]
+    fn routes() {
+        assert_eq!(Route::of(&Method::Get, "/"), Route::Index);
+        assert_eq!(
+            Route::of(&Method::Get, "/day/2020/11?x=1"),
+            Route::Day(2020, 11)
+        );
+        assert_eq!(Route::of(&Method::Get, "/day/2020/x"), Route::NotFound);
+        assert_eq!(Route::of(&Method::Get, "/day/11"), Route::NotFound);
+        assert_eq!(Route::of(&Method::Get, "/api/results"), Route::Results);
+        assert_eq!(
+            Route::of(&Method::Get, "/api/day/2020/3/"),
+            Route::DayResults(2020, 3)
+        );
+        assert_eq!(Route::of(&Method::Post, "/run"), Route::Run { json: false });
+        assert_eq!(Route::of(&Method::Get, "/run"), Route::NotFound);
+    }
+
+    #[test]
+    fn pages() {
+        let results = results();
+        let input = Input::Variant(PathBuf::from("../inputs"), "small".to_string());
+        let index = page(&results, &input, Route::Index);
+        assert_eq!(index.status, 200);
+        assert!(index
+            .body
+            .contains("<td>7 <span class=\"pass\">(pass)</span></td>"));
+        assert!(index
+            .body
+            .contains("<td>1 <span class=\"fail\">(fail)</span></td>"));
+        assert!(index
+            .body
+            .contains("<td>error <span class=\"fail\">(fail)</span></td>"));
+
+        let day = page(&results, &input, Route::Day(2020, 3));
+        assert!(day.body.contains("<pre>O.#\n.X&lt;\n</pre>"));
+        let error = page(&results, &input, Route::Day(2020, 4));
+        assert!(error
+            .body
+            .contains("<p class=\"fail\">bad &lt;input&gt;</p>"));
+        assert_eq!(page(&results, &input, Route::Day(2020, 5)).status, 404);
+
+        let json: serde_json::Value =
+            serde_json::from_str(&page(&results, &input, Route::DayResults(2020, 3)).body).unwrap();
+        assert_eq!(json["parts"][0]["answer"], 7);
+        assert_eq!(json["parts"][0]["verified"], "pass");
+        assert_eq!(json["parts"][0]["rendering"], "O.#\n.X<\n");
+        assert_eq!(json["parts"][1]["verified"], "fail");
+        assert_eq!(json["parts"][1]["explanation"], serde_json::Value::Null);
+
+        let run = page(&results, &input, Route::Run { json: false });
+        assert_eq!((run.status, run.location), (303, Some("/")));
+    }
+}
@@ -0,0 +1,150 @@
+use crate::cache::Origin;
+use crate::profile::{duration, Profile};
+use anyhow::Result;
+use puzzle::{Answer, Day, Explanation, Part};
+use std::fmt::{self, Display};
+use std::time::Duration;
+
+/// Everything learned from solving one day.
+#[derive(Debug)]
+pub struct Solved {
+    pub profile: Profile,
+    pub answers: Vec<(Part, Answer, Origin)>,
+    /// How each answer was reached, when asked for and the day can say.
+    pub explanations: Vec<(Part, Option<Explanation>)>,
+    /// Pictures of the puzzle as each part left it, when asked for and the day can draw one.
+    pub renderings: Vec<(Part, String)>,
+}
+
+impl Solved {
+    /// Whether any part ran out of time before it was answered.
+    fn timed_out(&self) -> bool {
+        self.answers
+            .iter()
+            .any(|(_, answer, _)| matches!(answer, Answer::TimedOut(_)))
+    }
+
+    /// Whether every part was answered.
+    fn solved(&self) -> bool {
+        self.answers.iter().all(|(_, answer, _)| answer.is_solved())
+    }
+
+    fn answer(&self, part: Part) -> String {
+        self.answers
+            .iter()
+            .find(|(p, ..)| *p == part)
+            .map(|(_, answer, origin)| match (answer, origin) {
+                (Answer::Unsolved(_), _) => "unsolved".to_string(),
+                (Answer::TimedOut(_), _) => "timed out".to_string(),
+                (answer, Origin::Fresh) => answer.to_string(),
+                (answer, Origin::Cached) => format!("{} (cached)", answer),
+            })
+            .unwrap_or_else(|| "-".to_string())
+    }
+}
+
+/// How many days of a run ended each way.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct Totals {
+    solved: usize,
+    unsolved: usize,
+    timed_out: usize,
+    failed: usize,
+}
+
+impl Totals {
+    fn add(&mut self, result: &Result<Solved>) {
+        match result {
+            Ok(solved) if solved.timed_out() => self.timed_out += 1,
+            Ok(solved) if solved.solved() => self.solved += 1,
+            Ok(_) => self.unsolved += 1,
+            Err(_) => self.failed += 1,
+        }
+    }
+}
+
+impl Display for Totals {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} solved, {} unsolved, {} timed out, {} failed",
+            self.solved, self.unsolved, self.timed_out, self.failed
+        )
+    }
+}
+
+/// Print every day's answers and time in calendar order, followed by the totals.
+///
+/// `elapsed` is the wall clock time of the whole run, to compare against the time spent solving.
+pub fn print(days: &[Day], results: &[Result<Solved>], elapsed: Duration, jobs: usize) {
+    println!(
+        "{:>4}  {:>3}  {:<24}  {:>20}  {:>20}  {:>10}",
+        "year", "day", "title", "part1", "part2", "time"
+    );
+    let mut solving = Duration::ZERO;
+    let mut totals = Totals::default();
+    for (day, result) in days.iter().zip(results) {
+        totals.add(result);
+        let (part1, part2, time) = match result {
+            Ok(solved) => {
+                solving += solved.profile.total();
+                (
+                    solved.answer(Part::One),
+                    solved.answer(Part::Two),
+                    Some(solved.profile.total()),
+                )
+            }
+            Err(_) => ("error".to_string(), "error".to_string(), None),
+        };
+        println!(
+            "{:>4}  {:>3}  {:<24}  {:>20}  {:>20}  {:>10}",
+            day.year,
+            day.day,
+            day.title,
+            part1,
+            part2,
+            duration(time)
+        );
+    }
+    println!(
+        "{} in {:.2?} ({:.2?} spent solving across {} thread(s))",
+        totals, elapsed, solving, jobs
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::anyhow;
+
+    fn solved(part1: Answer, part2: Answer) -> Result<Solved> {
+        Ok(Solved {
+            profile: Profile::default(),
+            answers: vec![
+                (Part::One, part1, Origin::Fresh),
+                (Part::Two, part2, Origin::Cached),
+            ],
+            explanations: vec![],
+            renderings: vec![],
+        })
+    }
+
+    #[test]
+    fn totals() {
+        let unsolved = || Answer::Unsolved("no answer found".to_string());
+        let mut totals = Totals::default();
+        for result in [
+            solved(Answer::Integer(1), Answer::Integer(2)),
+            solved(unsolved(), unsolved()),
+            solved(Answer::Integer(1), unsolved()),
+            solved(Answer::Integer(1), Answer::TimedOut(Duration::from_secs(1))),
+            Err(anyhow!("panicked")),
+        ] {
+            totals.add(&result);
+        }
+        assert_eq!(
+            totals.to_string(),
+            "1 solved, 2 unsolved, 1 timed out, 1 failed"
+        );
+    }
+}
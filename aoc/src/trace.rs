@@ -0,0 +1,99 @@
+use anyhow::{Context, Result};
+use clap::Args;
+use std::fmt::{self, Write};
+use std::fs::File;
+use std::path::PathBuf;
+use tracing::field::{Field, Visit};
+use tracing::{Event, Level, Subscriber};
+use tracing_chrome::{ChromeLayerBuilder, FlushGuard};
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::layer::{self, Layer};
+use tracing_subscriber::prelude::*;
+use tracing_subscriber::Registry;
+
+/// Command line options for tracing where the time goes.
+#[derive(Debug, Clone, Args)]
+pub struct TraceArgs {
+    /// Write a Chrome trace to this file, to open in Perfetto or chrome://tracing
+    #[arg(long)]
+    trace: Option<PathBuf>,
+    /// Finest spans to trace: `info` for each day's phases, `debug` adds the solvers' main
+    /// loops, and `trace` every step of their recursion
+    #[arg(long, default_value = "debug", requires = "trace")]
+    trace_level: LevelFilter,
+}
+
+impl TraceArgs {
+    /// Start tracing, if asked to, or else pass the solvers' events on to the logger. The trace
+    /// is written in full once the guard is dropped.
+    pub fn start(&self) -> Result<Option<FlushGuard>> {
+        let path = match &self.trace {
+            Some(path) => path,
+            None => {
+                to_log()?;
+                return Ok(None);
+            }
+        };
+        let file = File::create(path).with_context(|| format!("creating '{}'", path.display()))?;
+        let (chrome, guard) = ChromeLayerBuilder::new()
+            .writer(file)
+            .include_args(true)
+            .build();
+        tracing::subscriber::set_global_default(
+            Registry::default().with(chrome.with_filter(self.trace_level)),
+        )?;
+        Ok(Some(guard))
+    }
+}
+
+/// Pass the solvers' `tracing` events on to the logger, so `RUST_LOG` still shows them. Spans
+/// are left out; they are only wanted in a trace.
+pub fn to_log() -> Result<()> {
+    tracing::subscriber::set_global_default(Registry::default().with(Log))?;
+    Ok(())
+}
+
+/// A layer writing each event as a `log` record.
+struct Log;
+
+impl<S: Subscriber> Layer<S> for Log {
+    fn on_event(&self, event: &Event<'_>, _: layer::Context<'_, S>) {
+        let metadata = event.metadata();
+        let level = match *metadata.level() {
+            Level::ERROR => log::Level::Error,
+            Level::WARN => log::Level::Warn,
+            Level::INFO => log::Level::Info,
+            Level::DEBUG => log::Level::Debug,
+            Level::TRACE => log::Level::Trace,
+        };
+        if !log::log_enabled!(target: metadata.target(), level) {
+            return;
+        }
+        let mut message = Message::default();
+        event.record(&mut message);
+        log::logger().log(
+            &log::Record::builder()
+                .level(level)
+                .target(metadata.target())
+                .module_path(metadata.module_path())
+                .file(metadata.file())
+                .line(metadata.line())
+                .args(format_args!("{}", message.0))
+                .build(),
+        );
+    }
+}
+
+/// An event's message, followed by any other fields as `name=value`.
+#[derive(Default)]
+struct Message(String);
+
+impl Visit for Message {
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        let separator = if self.0.is_empty() { "" } else { " " };
+        let _ = match field.name() {
+            "message" => write!(self.0, "{}{:?}", separator, value),
+            name => write!(self.0, "{}{}={:?}", separator, name, value),
+        };
+    }
+}
@@ -0,0 +1,173 @@
+use crate::answers::{Answers, Recorded};
+use crate::input::variant_path;
+use puzzle::{Answer, Day, Part};
+use serde::Serialize;
+use std::fmt::Display;
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Status {
+    Pass,
+    Fail,
+    Missing,
+}
+
+impl Display for Status {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Status::Pass => write!(f, "pass"),
+            Status::Fail => write!(f, "FAIL"),
+            Status::Missing => write!(f, "missing"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    NoInput,
+    Error(String),
+    Answer(Answer),
+}
+
+/// The result of checking one part of one day against its recorded answer.
+#[derive(Debug)]
+pub struct Check {
+    pub year: u32,
+    pub day: u32,
+    pub variant: String,
+    pub part: Part,
+    pub expected: Option<Recorded>,
+    pub outcome: Outcome,
+}
+
+impl Check {
+    pub fn status(&self) -> Status {
+        match (&self.outcome, &self.expected) {
+            (Outcome::NoInput, _) => Status::Missing,
+            (Outcome::Error(_), _) => Status::Fail,
+            (Outcome::Answer(_), None) => Status::Missing,
+            (Outcome::Answer(actual), Some(expected)) if expected.matches(actual) => Status::Pass,
+            (Outcome::Answer(_), Some(_)) => Status::Fail,
+        }
+    }
+}
+
+/// Solve each day against every variant it has answers for (or just `variant`), and compare.
+pub fn verify(
+    days: &[Day],
+    inputs_dir: &Path,
+    variant: Option<&str>,
+    answers: &Answers,
+) -> Vec<Check> {
+    let mut checks = Vec::new();
+    for day in days {
+        let variants = match variant {
+            Some(variant) => vec![variant.to_string()],
+            None => answers.variants(day),
+        };
+        let variants = match variants.is_empty() {
+            true => vec!["full".to_string()],
+            false => variants,
+        };
+        for variant in variants {
+            let outcomes = solve(day, &variant_path(inputs_dir, day, &variant));
+            for (part, outcome) in Part::ALL.into_iter().zip(outcomes) {
+                checks.push(Check {
+                    year: day.year,
+                    day: day.day,
+                    variant: variant.clone(),
+                    part,
+                    expected: answers.get(day, &variant, part),
+                    outcome,
+                });
+            }
+        }
+    }
+    checks
+}
+
+fn solve(day: &Day, path: &Path) -> [Outcome; 2] {
+    if !path.exists() {
+        return [Outcome::NoInput, Outcome::NoInput];
+    }
+    match day.load(&path.to_string_lossy()) {
+        Ok(mut solution) => {
+            solution.analyse();
+            Part::ALL.map(|part| Outcome::Answer(solution.answer(part)))
+        }
+        Err(e) => {
+            let e = format!("{:#}", e);
+            [Outcome::Error(e.clone()), Outcome::Error(e)]
+        }
+    }
+}
+
+/// Print the checks as a table, followed by a summary line.
+pub fn print(checks: &[Check]) {
+    println!(
+        "{:>4}  {:>3}  {:<8}  {:<5}  {:>16}  {:>16}  status",
+        "year", "day", "variant", "part", "expected", "actual"
+    );
+    for check in checks {
+        let actual = match &check.outcome {
+            Outcome::NoInput => "no input".to_string(),
+            Outcome::Error(_) => "error".to_string(),
+            Outcome::Answer(Answer::Unsolved(_)) => "unsolved".to_string(),
+            Outcome::Answer(answer) => answer.to_string(),
+        };
+        print!(
+            "{:>4}  {:>3}  {:<8}  {:<5}  {:>16}  {:>16}  {}",
+            check.year,
+            check.day,
+            check.variant,
+            check.part,
+            check
+                .expected
+                .as_ref()
+                .map(|expected| expected.to_string())
+                .unwrap_or_default(),
+            actual,
+            check.status()
+        );
+        match &check.outcome {
+            Outcome::Error(e) | Outcome::Answer(Answer::Unsolved(e)) => println!(" ({})", e),
+            _ => println!(),
+        }
+    }
+    let count = |status| checks.iter().filter(|c| c.status() == status).count();
+    println!(
+        "{} passed, {} failed, {} missing",
+        count(Status::Pass),
+        count(Status::Fail),
+        count(Status::Missing)
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(expected: Option<i64>, outcome: Outcome) -> Status {
+        Check {
+            year: 2020,
+            day: 1,
+            variant: "small".to_string(),
+            part: Part::One,
+            expected: expected.map(Recorded::Integer),
+            outcome,
+        }
+        .status()
+    }
+
+    #[test]
+    fn status() {
+        let answer = |answer: Option<i64>| Outcome::Answer(answer.into());
+        assert_eq!(check(Some(1), answer(Some(1))), Status::Pass);
+        assert_eq!(check(Some(1), answer(Some(2))), Status::Fail);
+        assert_eq!(check(Some(1), answer(None)), Status::Fail);
+        assert_eq!(check(None, answer(Some(2))), Status::Missing);
+        assert_eq!(check(Some(1), Outcome::NoInput), Status::Missing);
+        assert_eq!(check(None, Outcome::Error("bad".into())), Status::Fail);
+    }
+}
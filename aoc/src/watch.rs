@@ -0,0 +1,182 @@
+use crate::cache::Cache;
+use crate::input::Input;
+use crate::{schedule, solve, Detail};
+use anyhow::{bail, Result};
+use log::info;
+use puzzle::{Answer, Day, Part};
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+/// When, and to what length, a file was last written, to notice it change; `None` if missing.
+type Stamp = Option<(SystemTime, u64)>;
+
+fn stamp(path: &Path) -> Stamp {
+    let metadata = fs::metadata(path).ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
+}
+
+/// One day being watched: where its input is, and what it last gave.
+struct Watched {
+    day: Day,
+    path: PathBuf,
+    stamp: Stamp,
+    /// The answers of the last run which solved the day, to compare the next run against.
+    answers: Option<Vec<(Part, Answer)>>,
+}
+
+/// Re-solves days whenever their input changes, showing how each answer changed since the
+/// previous run.
+pub struct Watcher {
+    watched: Vec<Watched>,
+    parts: Vec<Part>,
+    input: Input,
+    cache: Cache,
+    timeout: Option<Duration>,
+}
+
+impl Watcher {
+    /// A watcher which has not yet solved any day. Only inputs read from files can be watched.
+    pub fn new(
+        days: &[Day],
+        parts: Vec<Part>,
+        input: Input,
+        cache: Cache,
+        timeout: Option<Duration>,
+    ) -> Result<Self> {
+        let mut watched = Vec::new();
+        for day in days {
+            let Some(path) = input.path(day) else {
+                bail!("{} cannot be watched; give an input file or variant", input);
+            };
+            watched.push(Watched {
+                day: *day,
+                path,
+                stamp: None,
+                answers: None,
+            });
+        }
+        Ok(Self {
+            watched,
+            parts,
+            input,
+            cache,
+            timeout,
+        })
+    }
+
+    /// Solve every day, then poll their inputs every `interval`, re-solving any day whose input
+    /// changed, until the process is stopped.
+    pub fn run(&mut self, out: &mut impl Write, interval: Duration) -> Result<()> {
+        for watched in &self.watched {
+            info!("watching {} for {}", watched.path.display(), watched.day);
+        }
+        self.poll(out, true)?;
+        loop {
+            thread::sleep(interval);
+            self.poll(out, false)?;
+        }
+    }
+
+    /// Re-solve each day whose input has changed since it was last solved (or every day, if
+    /// `all`), writing how its answers changed.
+    fn poll(&mut self, out: &mut impl Write, all: bool) -> Result<()> {
+        for watched in &mut self.watched {
+            let stamp = stamp(&watched.path);
+            if !all && stamp == watched.stamp {
+                continue;
+            }
+            watched.stamp = stamp;
+            // A solver which panics fails this run, as a malformed input does, and is watched on
+            let solved = schedule::catch(|| {
+                solve(
+                    &watched.day,
+                    &self.parts,
+                    &self.input,
+                    &self.cache,
+                    self.timeout,
+                    Detail::default(),
+                )
+            });
+            let answers = solved.map(|solved| {
+                let answers = solved.answers.into_iter();
+                answers.map(|(part, answer, _)| (part, answer)).collect()
+            });
+            for line in changes(&watched.day, watched.answers.as_deref(), &answers) {
+                writeln!(out, "{}", line)?;
+            }
+            out.flush()?;
+            // A run which failed leaves the last answers to compare against
+            if let Ok(answers) = answers {
+                watched.answers = Some(answers);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// How a day's answers changed from `before`, if it was solved before, to `after`.
+fn changes(
+    day: &Day,
+    before: Option<&[(Part, Answer)]>,
+    after: &Result<Vec<(Part, Answer)>>,
+) -> Vec<String> {
+    let answers = match after {
+        Ok(answers) => answers,
+        Err(e) => return vec![format!("{}: {:#}", day, e)],
+    };
+    answers
+        .iter()
+        .map(|(part, answer)| {
+            let previous = before
+                .and_then(|before| before.iter().find(|(p, _)| p == part))
+                .map(|(_, answer)| answer);
+            match previous {
+                None => format!("{} {}: {}", day, part, answer),
+                Some(previous) if previous == answer => {
+                    format!("{} {}: {} (unchanged)", day, part, answer)
+                }
+                Some(previous) => format!("{} {}: {} -> {}", day, part, previous, answer),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::anyhow;
+
+    #[test]
+    fn answer_changes() {
+        let day = Day::of::<day13::Solution>();
+        let answers = |part1: i64, part2: i64| {
+            vec![
+                (Part::One, Answer::Integer(part1)),
+                (Part::Two, Answer::Integer(part2)),
+            ]
+        };
+        let first = answers(295, 1068781);
+        assert_eq!(
+            changes(&day, None, &Ok(first.clone())),
+            vec!["2020 day 13 part1: 295", "2020 day 13 part2: 1068781"]
+        );
+        assert_eq!(
+            changes(&day, Some(&first), &Ok(answers(295, 3417))),
+            vec![
+                "2020 day 13 part1: 295 (unchanged)",
+                "2020 day 13 part2: 1068781 -> 3417"
+            ]
+        );
+        assert_eq!(
+            changes(
+                &day,
+                Some(&first),
+                &Err(anyhow!("expected a list of buses"))
+            ),
+            vec!["2020 day 13: expected a list of buses"]
+        );
+    }
+}
@@ -0,0 +1,213 @@
+//! Cellular automata over `N`-dimensional integer lattices.
+
+use rustc_hash::FxHashMap;
+use std::borrow::Cow;
+use std::fmt::Debug;
+
+/// A cell's coordinates.
+pub type Point<const N: usize> = [i64; N];
+
+/// How each cell of an automaton changes from one generation to the next.
+pub trait Rules<const N: usize> {
+    /// The state of a cell. `Default` is the quiescent state, which cells off the map are in.
+    type State: Copy + Eq + Default + Debug;
+
+    /// The cells whose states decide `point`'s next state.
+    ///
+    /// Must be symmetric: if `q` is in `p`'s neighbourhood, `p` is in `q`'s.
+    fn neighbourhood(&self, point: &Point<N>) -> Cow<'_, [Point<N>]>;
+
+    /// The next state of a cell in `state`, given the states of the cells in its neighbourhood
+    /// that are not quiescent.
+    ///
+    /// A quiescent cell with a quiescent neighbourhood must stay quiescent.
+    fn transition(&self, state: Self::State, neighbours: &[Self::State]) -> Self::State;
+}
+
+/// The Moore neighbourhood: every point within one step along each axis, except `point` itself.
+pub fn moore<const N: usize>(point: &Point<N>) -> Vec<Point<N>> {
+    let mut points = vec![*point];
+    for axis in 0..N {
+        points = points
+            .into_iter()
+            .flat_map(|p| {
+                [-1, 0, 1].map(|d| {
+                    let mut p = p;
+                    p[axis] += d;
+                    p
+                })
+            })
+            .collect();
+    }
+    points.retain(|p| p != point);
+    points
+}
+
+/// The cells of an automaton that are not quiescent.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct World<const N: usize, S> {
+    cells: FxHashMap<Point<N>, S>,
+}
+
+impl<const N: usize, S: Copy + Eq + Default> World<N, S> {
+    pub fn new() -> Self {
+        Self {
+            cells: FxHashMap::default(),
+        }
+    }
+
+    pub fn get(&self, point: &Point<N>) -> S {
+        self.cells.get(point).copied().unwrap_or_default()
+    }
+
+    pub fn set(&mut self, point: Point<N>, state: S) {
+        if state == S::default() {
+            self.cells.remove(&point);
+        } else {
+            self.cells.insert(point, state);
+        }
+    }
+
+    /// Every cell that is not quiescent.
+    pub fn iter(&self) -> impl Iterator<Item = (&Point<N>, &S)> {
+        self.cells.iter()
+    }
+
+    /// The number of cells in `state`, which must not be the quiescent state.
+    pub fn count(&self, state: S) -> usize {
+        self.cells.values().filter(|&&s| s == state).count()
+    }
+}
+
+impl<const N: usize, S: Copy + Eq + Default> Default for World<N, S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize, S: Copy + Eq + Default> FromIterator<(Point<N>, S)> for World<N, S> {
+    fn from_iter<I: IntoIterator<Item = (Point<N>, S)>>(iter: I) -> Self {
+        let mut world = Self::new();
+        for (point, state) in iter {
+            world.set(point, state);
+        }
+        world
+    }
+}
+
+/// A world evolving under a set of rules.
+#[derive(Debug)]
+pub struct Automaton<const N: usize, R: Rules<N>> {
+    rules: R,
+    world: World<N, R::State>,
+    generation: usize,
+}
+
+impl<const N: usize, R: Rules<N>> Automaton<N, R> {
+    pub fn new(rules: R, world: World<N, R::State>) -> Self {
+        Self {
+            rules,
+            world,
+            generation: 0,
+        }
+    }
+
+    pub fn world(&self) -> &World<N, R::State> {
+        &self.world
+    }
+
+    /// The number of generations run so far.
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+
+    /// Advance one generation, returning whether any cell changed.
+    pub fn step(&mut self) -> bool {
+        let mut next = World {
+            cells: FxHashMap::with_capacity_and_hasher(self.world.cells.len(), Default::default()),
+        };
+        let mut changed = false;
+        let mut update = |point: Point<N>, state: R::State, neighbours: &[R::State]| {
+            let new = self.rules.transition(state, neighbours);
+            changed |= new != state;
+            next.set(point, new);
+        };
+
+        // Only live cells, and the quiescent cells they neighbour, can change. Live cells look
+        // up their neighbours; thanks to symmetry, quiescent ones are handed the states of
+        // their live neighbours instead.
+        let mut neighbours = Vec::new();
+        let mut woken: FxHashMap<Point<N>, Vec<R::State>> = FxHashMap::default();
+        for (point, &state) in &self.world.cells {
+            neighbours.clear();
+            for neighbour in self.rules.neighbourhood(point).iter() {
+                match self.world.cells.get(neighbour) {
+                    Some(&neighbour) => neighbours.push(neighbour),
+                    None => woken.entry(*neighbour).or_default().push(state),
+                }
+            }
+            update(*point, state, &neighbours);
+        }
+        for (point, neighbours) in woken {
+            update(point, R::State::default(), &neighbours);
+        }
+
+        self.world = next;
+        self.generation += 1;
+        changed
+    }
+
+    /// Run `generations` more generations.
+    pub fn run(&mut self, generations: usize) -> &World<N, R::State> {
+        for _ in 0..generations {
+            self.step();
+        }
+        &self.world
+    }
+
+    /// Run until a generation changes nothing.
+    pub fn run_until_stable(&mut self) -> &World<N, R::State> {
+        while self.step() {}
+        &self.world
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Conway's Game of Life.
+    struct Life;
+
+    impl Rules<2> for Life {
+        type State = bool;
+
+        fn neighbourhood(&self, point: &Point<2>) -> Cow<'_, [Point<2>]> {
+            Cow::Owned(moore(point))
+        }
+
+        fn transition(&self, alive: bool, neighbours: &[bool]) -> bool {
+            let count = neighbours.iter().filter(|&&n| n).count();
+            count == 3 || (alive && count == 2)
+        }
+    }
+
+    fn world(points: &[Point<2>]) -> World<2, bool> {
+        points.iter().map(|&p| (p, true)).collect()
+    }
+
+    #[test]
+    fn life() {
+        assert_eq!(moore(&[0, 0, 0]).len(), 26);
+
+        let blinker = world(&[[0, -1], [0, 0], [0, 1]]);
+        let mut automaton = Automaton::new(Life, blinker.clone());
+        assert_eq!(automaton.run(1), &world(&[[-1, 0], [0, 0], [1, 0]]));
+        assert_eq!(automaton.run(1), &blinker);
+
+        let block = world(&[[0, 0], [0, 1], [1, 0], [1, 1]]);
+        let mut automaton = Automaton::new(Life, block.clone());
+        assert_eq!(automaton.run_until_stable(), &block);
+        assert_eq!(automaton.generation(), 1);
+    }
+}
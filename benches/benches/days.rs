@@ -0,0 +1,76 @@
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
+use puzzle::Puzzle;
+use std::fs;
+use std::path::PathBuf;
+
+/// Input variants benchmarked for every day, where present.
+const VARIANTS: [&str; 2] = ["small", "full"];
+
+fn inputs_dir() -> PathBuf {
+    std::env::var_os("AOC_INPUTS_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../inputs"))
+}
+
+/// Benchmark parsing, `analyse()` and each part separately, for each available input.
+fn bench<P: Puzzle>(c: &mut Criterion) {
+    for variant in VARIANTS {
+        let path =
+            inputs_dir()
+                .join(P::YEAR.to_string())
+                .join(format!("input.day{}.{}", P::DAY, variant));
+        let input = match fs::read_to_string(&path) {
+            Ok(input) => input,
+            Err(_) => continue,
+        };
+
+        let mut group = c.benchmark_group(format!("{}/day{}/{}", P::YEAR, P::DAY, variant));
+        group.bench_function("parse", |b| b.iter(|| P::parse(black_box(&input)).unwrap()));
+        group.bench_function("analyse", |b| {
+            b.iter_batched(
+                || P::parse(&input).unwrap(),
+                |mut solution| {
+                    solution.analyse();
+                    solution
+                },
+                BatchSize::LargeInput,
+            )
+        });
+
+        let mut solution = P::parse(&input).unwrap();
+        solution.analyse();
+        group.bench_function("part1", |b| b.iter(|| black_box(&solution).answer_part1()));
+        group.bench_function("part2", |b| b.iter(|| black_box(&solution).answer_part2()));
+        group.finish();
+    }
+}
+
+/// Every day, in calendar order.
+fn days(c: &mut Criterion) {
+    bench::<day1::Solution>(c);
+    bench::<day2::Solution>(c);
+    bench::<day3::Solution>(c);
+    bench::<day4::Solution>(c);
+    bench::<day5::Solution>(c);
+    bench::<day6::Solution>(c);
+    bench::<day7::Solution>(c);
+    bench::<day8::Solution>(c);
+    bench::<day9::Solution>(c);
+    bench::<day11::Solution>(c);
+    bench::<day12::Solution>(c);
+    bench::<day13::Solution>(c);
+    bench::<day14::Solution>(c);
+    bench::<day15::Solution>(c);
+    bench::<day16::Solution>(c);
+    bench::<day17::Solution>(c);
+    bench::<day18::Solution>(c);
+    bench::<day19::Solution>(c);
+}
+
+criterion_group! {
+    name = benches;
+    // Some parts (e.g. day15's 30 million turns) take seconds per iteration
+    config = Criterion::default().sample_size(10);
+    targets = days
+}
+criterion_main!(benches);
@@ -0,0 +1,37 @@
+use rand::seq::SliceRandom;
+use rand::Rng;
+use std::io::Write;
+
+/// `size` expense entries: exactly one pair and one triple summing to 2020, padded with entries
+/// too large to be part of either.
+pub fn input(size: usize, rng: &mut impl Rng, out: &mut dyn Write) -> anyhow::Result<()> {
+    let mut entries = loop {
+        let pair = rng.gen_range(1..1010);
+        let first = rng.gen_range(1..672);
+        let second = rng.gen_range(first + 1..(2020 - first) / 2);
+        let planted = vec![pair, 2020 - pair, first, second, 2020 - first - second];
+        if ways(&planted, 2, 2020) == 1 && ways(&planted, 3, 2020) == 1 {
+            break planted;
+        }
+    };
+    let limit = 2021 + 100 * size as i64;
+    while entries.len() < size {
+        entries.push(rng.gen_range(2021..limit));
+    }
+    entries.shuffle(rng);
+    for entry in entries {
+        writeln!(out, "{}", entry)?;
+    }
+    Ok(())
+}
+
+/// The number of ways of choosing `count` of `entries` that sum to `total`.
+fn ways(entries: &[i64], count: usize, total: i64) -> usize {
+    match (count, entries.split_first()) {
+        (0, _) => (total == 0) as usize,
+        (_, None) => 0,
+        (_, Some((&first, rest))) => {
+            ways(rest, count - 1, total - first) + ways(rest, count, total)
+        }
+    }
+}
@@ -1,52 +1,77 @@
-use anyhow::Context;
+use puzzle::{check_lines, parse_at, Answer, Explanation, ParseError, Part, Puzzle};
+use rand::rngs::StdRng;
 use std::collections::HashSet;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::io::{BufRead, Write};
 
-pub fn load(filename: &str) -> anyhow::Result<Solution> {
-    let file = File::open(filename).context(format!("loading '{}'", filename))?;
-
-    let reader = BufReader::new(file);
-
-    let mut solution = Solution::default();
-    for line in reader.lines() {
-        let line = line?;
-        solution.add(line.parse()?);
-    }
-    Ok(solution)
-}
+mod generate;
 
 #[derive(Debug, Default)]
 pub struct Solution {
     data: HashSet<i64>,
-    answer1: Option<i64>,
-    answer2: Option<i64>,
+    /// The entries summing to 2020 for each part.
+    answer1: Option<Vec<i64>>,
+    answer2: Option<Vec<i64>>,
 }
 
-impl Solution {
-    pub fn analyse(&mut self) {
+impl Puzzle for Solution {
+    const YEAR: u32 = 2020;
+    const DAY: u32 = 1;
+    const TITLE: &'static str = "Report Repair";
+    const VERSION: &'static str = env!("CARGO_PKG_VERSION");
+
+    fn from_reader(reader: impl BufRead) -> anyhow::Result<Self> {
+        let mut solution = Solution::default();
+        for (idx, line) in reader.lines().enumerate() {
+            let line = line?;
+            let value = parse_at(&line, 0).map_err(|e| e.at_line(idx + 1))?;
+            solution.add(value);
+        }
+        Ok(solution)
+    }
+
+    fn check(input: &str) -> Vec<ParseError> {
+        check_lines(input, |line| parse_at::<i64>(line, 0).map(drop))
+    }
+
+    fn generate(size: usize, rng: &mut StdRng, out: &mut dyn Write) -> anyhow::Result<()> {
+        generate::input(size, rng, out)
+    }
+
+    fn analyse(&mut self) {
         self.answer1 = None;
         for (idx1, v1) in self.data.iter().enumerate() {
             if self.data.contains(&(2020 - v1)) {
-                self.answer1 = Some(v1 * (2020 - v1));
+                self.answer1 = Some(vec![*v1, 2020 - v1]);
             }
             for (idx2, v2) in self.data.iter().enumerate() {
                 if idx2 <= idx1 {
                     continue;
                 }
                 if self.data.contains(&(2020 - (v1 + v2))) {
-                    self.answer2 = Some(v1 * v2 * (2020 - (v1 + v2)));
+                    self.answer2 = Some(vec![*v1, *v2, 2020 - (v1 + v2)]);
                 }
             }
         }
     }
 
-    pub fn answer_part1(&self) -> Option<i64> {
-        self.answer1
+    fn answer_part1(&self) -> Answer {
+        Self::product(&self.answer1).into()
     }
 
-    pub fn answer_part2(&self) -> Option<i64> {
-        self.answer2
+    fn answer_part2(&self) -> Answer {
+        Self::product(&self.answer2).into()
+    }
+
+    fn explain(&self, part: Part) -> Option<Explanation> {
+        let entries = match part {
+            Part::One => self.answer1.as_ref()?,
+            Part::Two => self.answer2.as_ref()?,
+        };
+        Some(Explanation::record([
+            ("entries", entries.clone().into()),
+            ("sum", entries.iter().sum::<i64>().into()),
+            ("product", entries.iter().product::<i64>().into()),
+        ]))
     }
 }
 
@@ -54,4 +79,8 @@ impl Solution {
     fn add(&mut self, value: i64) {
         self.data.insert(value);
     }
+
+    fn product(entries: &Option<Vec<i64>>) -> Option<i64> {
+        entries.as_ref().map(|entries| entries.iter().product())
+    }
 }
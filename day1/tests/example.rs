@@ -0,0 +1,10 @@
+use day1::Solution;
+use puzzle::{Answer, Puzzle};
+
+#[test]
+fn example() {
+    let mut solution = Solution::parse(include_str!("../../inputs/2020/input.day1.small")).unwrap();
+    solution.analyse();
+    assert_eq!(solution.answer_part1(), Answer::Integer(514579));
+    assert_eq!(solution.answer_part2(), Answer::Integer(241861950));
+}
@@ -0,0 +1,29 @@
+use crate::{Position, Solution};
+use grid::Grid;
+use rand::Rng;
+use std::io::Write;
+
+/// A `size` by `size` waiting area, mostly empty seats.
+pub fn input(size: usize, rng: &mut impl Rng, out: &mut dyn Write) -> anyhow::Result<()> {
+    let mut chairs = Grid::new(size, size, Position::Floor);
+    for y in 0..size {
+        for x in 0..size {
+            if rng.gen_bool(0.85) {
+                chairs[(x, y)] = Position::EmptySeat;
+            }
+        }
+    }
+    // Some layouts never settle, with seats flipping back and forth forever; pull those seats up
+    // until nobody is left moving
+    loop {
+        let cycling = Solution::cycling(&chairs);
+        if cycling.is_empty() {
+            break;
+        }
+        for seat in cycling {
+            chairs[seat] = Position::Floor;
+        }
+    }
+    write!(out, "{}", chairs)?;
+    Ok(())
+}
@@ -1,232 +1,236 @@
+use automaton::{Automaton, Point, Rules};
+use grid::{Cell, Grid, ADJACENT};
 use log::debug;
-use std::cmp::max;
-use std::collections::HashMap;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
-
-pub fn load(filename: &str) -> anyhow::Result<Solution> {
-    let file = File::open(filename)?;
-
-    let reader = BufReader::new(file);
-    let mut solution = Solution::new();
-    for (y, line) in reader.lines().enumerate() {
-        let line = line?;
-        for (x, c) in line.chars().enumerate() {
-            solution.set(x, y, c);
-        }
-    }
+use puzzle::{Answer, Cancel, ParseError, Part, Puzzle};
+use rand::rngs::StdRng;
+use std::borrow::Cow;
+use std::io::{BufRead, Write};
 
-    Ok(solution)
-}
+mod generate;
 
 #[derive(Debug, Default)]
 pub struct Solution {
-    chairs: HashMap<(i64, i64), Position>,
-    max_x: i64,
-    max_y: i64,
+    chairs: Grid<Position>,
+    cancel: Cancel,
 
-    answer_part1: Option<i64>,
-    answer_part2: Option<i64>,
+    /// The seats once each part's rules have settled them, or `None` if they never did.
+    settled_part1: Option<Grid<Position>>,
+    settled_part2: Option<Grid<Position>>,
 }
 
 impl Solution {
-    fn new() -> Self {
-        Self::default()
+    fn new(chairs: Grid<Position>) -> Self {
+        Self {
+            chairs,
+            ..Default::default()
+        }
+    }
+}
+
+impl Puzzle for Solution {
+    const YEAR: u32 = 2020;
+    const DAY: u32 = 11;
+    const TITLE: &'static str = "Seating System";
+    const VERSION: &'static str = env!("CARGO_PKG_VERSION");
+
+    fn from_reader(reader: impl BufRead) -> anyhow::Result<Self> {
+        Ok(Solution::new(Grid::from_reader(reader)?))
+    }
+
+    fn check(input: &str) -> Vec<ParseError> {
+        Grid::<Position>::check(input)
+    }
+
+    fn generate(size: usize, rng: &mut StdRng, out: &mut dyn Write) -> anyhow::Result<()> {
+        generate::input(size, rng, out)
+    }
+
+    fn set_cancel(&mut self, cancel: Cancel) {
+        self.cancel = cancel;
     }
 
-    pub fn analyse(&mut self) {
-        self.answer_part1 = self.analyse_part1();
-        self.answer_part2 = self.analyse_part2();
+    fn analyse(&mut self) {
+        self.settled_part1 = self.settle(4, Self::adjacent);
+        self.settled_part2 = self.settle(5, Self::line_of_sight);
     }
 
-    pub fn answer_part1(&self) -> Option<i64> {
-        self.answer_part1
+    fn answer_part1(&self) -> Answer {
+        self.settled_part1.as_ref().map(Self::occupied).into()
     }
 
-    pub fn answer_part2(&self) -> Option<i64> {
-        self.answer_part2
+    fn answer_part2(&self) -> Answer {
+        self.settled_part2.as_ref().map(Self::occupied).into()
+    }
+
+    /// The seats once the part's rules have settled them.
+    fn render(&self, part: Part) -> Option<String> {
+        let settled = match part {
+            Part::One => self.settled_part1.as_ref()?,
+            Part::Two => self.settled_part2.as_ref()?,
+        };
+        Some(settled.to_string())
     }
 }
 
 impl Solution {
-    fn analyse_part1(&self) -> Option<i64> {
-        let mut next = self.chairs.clone();
-        let mut pass = 0;
-        loop {
-            pass += 1;
-            debug!("P1 Pass {}", pass);
-            let n = self.pass_part1(&next);
-            next = n.0;
-            let changed = n.1;
-            Self::display(&next, self.max_x, self.max_y);
-            if !changed {
-                break;
-            }
-        }
-        let occupied = Self::total_occupied(&next);
-        Some(occupied)
-    }
-
-    fn analyse_part2(&self) -> Option<i64> {
-        let mut next = self.chairs.clone();
-        let mut pass = 0;
-        loop {
-            pass += 1;
-            debug!("P2 Pass {}", pass);
-            let n = self.pass_part2(&next);
-            next = n.0;
-            let changed = n.1;
-            Self::display(&next, self.max_x, self.max_y);
-            if !changed {
-                break;
-            }
-        }
-        let occupied = Self::total_occupied(&next);
-        Some(occupied)
+    fn occupied(seats: &Grid<Position>) -> i64 {
+        let occupied = seats
+            .iter()
+            .filter(|(_, &seat)| seat == Position::OccupiedSeat);
+        occupied.count() as i64
     }
 
-    fn pass_part1(
+    /// Apply the seating rules until nobody moves.
+    ///
+    /// `visible` gives the seats considered by the occupant of the seat at `(x, y)`. Some
+    /// layouts never settle, with seats flipping back and forth until cancelled.
+    fn settle(
         &self,
-        current: &HashMap<(i64, i64), Position>,
-    ) -> (HashMap<(i64, i64), Position>, bool) {
-        let mut next = HashMap::new();
-        let mut changed = false;
-        for ((x, y), position) in current {
-            let num_occupied = Self::num_occupied(current, *x, *y);
-            let next_position = match position {
-                Position::EmptySeat if num_occupied == 0 => {
-                    changed = true;
-                    Position::OccupiedSeat
-                }
-                Position::OccupiedSeat if num_occupied >= 4 => {
-                    changed = true;
-                    Position::EmptySeat
-                }
-                Position::EmptySeat => Position::EmptySeat,
-                Position::OccupiedSeat => Position::OccupiedSeat,
-                Position::Floor => Position::Floor,
-            };
-            next.insert((*x, *y), next_position);
+        tolerance: usize,
+        visible: impl Fn(&Grid<Position>, usize, usize) -> Vec<(usize, usize)>,
+    ) -> Option<Grid<Position>> {
+        let mut automaton = Self::seating(&self.chairs, tolerance, visible);
+        while automaton.step() {
+            if self.cancel.is_cancelled() {
+                return None;
+            }
         }
-        (next, changed)
+        debug!("settled after {} passes", automaton.generation());
+        let world = automaton.world();
+        Some(
+            self.chairs
+                .map(|(x, y), _| world.get(&[x as i64, y as i64])),
+        )
     }
 
-    fn pass_part2(
-        &self,
-        current: &HashMap<(i64, i64), Position>,
-    ) -> (HashMap<(i64, i64), Position>, bool) {
-        let mut next = HashMap::new();
-        let mut changed = false;
-        for ((x, y), position) in current {
-            let num_occupied_los = Self::num_occupied_los(current, *x, *y, self.max_x, self.max_y);
-            let next_position = match position {
-                Position::EmptySeat if num_occupied_los == 0 => {
-                    changed = true;
-                    Position::OccupiedSeat
-                }
-                Position::OccupiedSeat if num_occupied_los >= 5 => {
-                    changed = true;
-                    Position::EmptySeat
+    /// The seats that never settle under either part's rules, flipping between empty and occupied
+    /// forever. Empty when both parts settle.
+    fn cycling(chairs: &Grid<Position>) -> Vec<(usize, usize)> {
+        // Cycles longer than two passes have not been seen, but give up on them eventually
+        let limit = 10 * (chairs.width() + chairs.height()) + 1000;
+        let rules = [
+            (4, Self::adjacent as fn(&_, _, _) -> _),
+            (5, Self::line_of_sight),
+        ];
+        for (tolerance, visible) in rules {
+            let mut automaton = Self::seating(chairs, tolerance, visible);
+            let mut previous = [automaton.world().clone(), automaton.world().clone()];
+            while automaton.step() {
+                let world = automaton.world();
+                if *world == previous[0] || automaton.generation() > limit {
+                    return world
+                        .iter()
+                        .filter(|&(point, &position)| previous[1].get(point) != position)
+                        .map(|(&[x, y], _)| (x as usize, y as usize))
+                        .collect();
                 }
-                Position::EmptySeat => Position::EmptySeat,
-                Position::OccupiedSeat => Position::OccupiedSeat,
-                Position::Floor => Position::Floor,
-            };
-            next.insert((*x, *y), next_position);
+                previous.swap(0, 1);
+                previous[1] = world.clone();
+            }
         }
-        (next, changed)
+        Vec::new()
     }
 
-    fn total_occupied(current: &HashMap<(i64, i64), Position>) -> i64 {
-        current
+    fn seating(
+        chairs: &Grid<Position>,
+        tolerance: usize,
+        visible: impl Fn(&Grid<Position>, usize, usize) -> Vec<(usize, usize)>,
+    ) -> Automaton<2, Seating> {
+        let point = |(x, y): (usize, usize)| [x as i64, y as i64];
+        let neighbours = chairs.map(|(x, y), &position| match position {
+            Position::Floor => Vec::new(),
+            _ => visible(chairs, x, y).into_iter().map(point).collect(),
+        });
+        let world = chairs
             .iter()
-            .filter(|((_x, _y), &position)| position == Position::OccupiedSeat)
-            .count() as i64
-    }
-
-    fn display(current: &HashMap<(i64, i64), Position>, max_x: i64, max_y: i64) {
-        for y in 0..=max_y {
-            let mut line = String::new();
-            for x in 0..=max_x {
-                line += match current.get(&(x, y)).unwrap_or(&Position::Floor) {
-                    Position::Floor => ".",
-                    Position::EmptySeat => "L",
-                    Position::OccupiedSeat => "#",
-                };
-            }
-            debug!("{}", line);
-        }
+            .map(|(seat, &position)| (point(seat), position))
+            .collect();
+        Automaton::new(
+            Seating {
+                neighbours,
+                tolerance,
+            },
+            world,
+        )
     }
 
-    fn set(&mut self, x: usize, y: usize, c: char) {
-        let m = match c {
-            'L' => Position::EmptySeat,
-            '.' => Position::Floor,
-            '#' => Position::OccupiedSeat,
-            _ => unreachable!(),
-        };
-        self.chairs.insert((x as i64, y as i64), m);
-        self.max_x = max(self.max_x, x as i64);
-        self.max_y = max(self.max_y, y as i64);
+    fn adjacent(chairs: &Grid<Position>, x: usize, y: usize) -> Vec<(usize, usize)> {
+        chairs
+            .neighbours8(x, y)
+            .filter(|(_, &position)| position != Position::Floor)
+            .map(|(seat, _)| seat)
+            .collect()
     }
 
-    fn num_occupied(chairs: &HashMap<(i64, i64), Position>, x: i64, y: i64) -> i64 {
-        let mut occupied = 0;
-        for dy in -1..=1_i64 {
-            for dx in -1..=1_i64 {
-                if dx.abs() + dy.abs() == 0 {
-                    continue;
-                }
-                if let Position::OccupiedSeat = chairs
-                    .get(&(x + dx, y + dy))
-                    .unwrap_or(&Position::EmptySeat)
-                {
-                    occupied += 1;
-                }
-            }
+    fn line_of_sight(chairs: &Grid<Position>, x: usize, y: usize) -> Vec<(usize, usize)> {
+        ADJACENT
+            .into_iter()
+            .filter_map(|direction| {
+                chairs
+                    .line_of_sight(x, y, direction)
+                    .find(|(_, &position)| position != Position::Floor)
+                    .map(|(seat, _)| seat)
+            })
+            .collect()
+    }
+}
+
+/// The seating rules: a seat fills when none of the seats its occupant considers are occupied,
+/// and empties once `tolerance` of them are.
+struct Seating {
+    /// The seats considered from each seat.
+    neighbours: Grid<Vec<Point<2>>>,
+    tolerance: usize,
+}
+
+impl Rules<2> for Seating {
+    type State = Position;
+
+    fn neighbourhood(&self, point: &Point<2>) -> Cow<'_, [Point<2>]> {
+        match self.neighbours.get(point[0], point[1]) {
+            Some(neighbours) => Cow::Borrowed(neighbours),
+            None => Cow::Owned(Vec::new()),
         }
-        occupied
-    }
-
-    fn num_occupied_los(
-        chairs: &HashMap<(i64, i64), Position>,
-        x: i64,
-        y: i64,
-        max_x: i64,
-        max_y: i64,
-    ) -> i64 {
-        let mut occupied = 0;
-        for dy in -1..=1_i64 {
-            for dx in -1..=1_i64 {
-                if dx.abs() + dy.abs() == 0 {
-                    continue;
-                }
-                let mut pos_x = x + dx;
-                let mut pos_y = y + dy;
-                let position = loop {
-                    if pos_x < 0 || pos_y < 0 || pos_x > max_x || pos_y > max_y {
-                        break Position::EmptySeat;
-                    }
-                    match chairs.get(&(pos_x, pos_y)) {
-                        Some(Position::OccupiedSeat) => break Position::OccupiedSeat,
-                        Some(Position::EmptySeat) => break Position::EmptySeat,
-                        _ => {}
-                    };
-                    pos_x += dx;
-                    pos_y += dy;
-                };
-                if let Position::OccupiedSeat = position {
-                    occupied += 1;
-                }
-            }
+    }
+
+    fn transition(&self, position: Position, neighbours: &[Position]) -> Position {
+        let occupied = neighbours
+            .iter()
+            .filter(|&&position| position == Position::OccupiedSeat)
+            .count();
+        match position {
+            Position::EmptySeat if occupied == 0 => Position::OccupiedSeat,
+            Position::OccupiedSeat if occupied >= self.tolerance => Position::EmptySeat,
+            position => position,
         }
-        occupied
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Copy)]
+#[derive(Debug, Clone, PartialEq, Eq, Copy, Default)]
 enum Position {
     EmptySeat,
+    #[default]
     Floor,
     OccupiedSeat,
 }
+
+impl Cell for Position {
+    const EXPECTED: &'static str = "'L', '.' or '#'";
+
+    fn from_char(c: char) -> Option<Self> {
+        match c {
+            'L' => Some(Position::EmptySeat),
+            '.' => Some(Position::Floor),
+            '#' => Some(Position::OccupiedSeat),
+            _ => None,
+        }
+    }
+
+    fn to_char(self) -> char {
+        match self {
+            Position::EmptySeat => 'L',
+            Position::Floor => '.',
+            Position::OccupiedSeat => '#',
+        }
+    }
+}
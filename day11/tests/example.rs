@@ -0,0 +1,43 @@
+use day11::Solution;
+use puzzle::{Answer, Cancel, Part, Puzzle};
+use std::time::{Duration, Instant};
+
+#[test]
+fn example() {
+    let mut solution =
+        Solution::parse(include_str!("../../inputs/2020/input.day11.small")).unwrap();
+    solution.analyse();
+    assert_eq!(solution.answer_part1(), Answer::Integer(37));
+    assert_eq!(solution.answer_part2(), Answer::Integer(26));
+}
+
+#[test]
+fn never_settles() {
+    let mut solution =
+        Solution::parse(include_str!("../../inputs/2020/input.day11.unsettled")).unwrap();
+    solution.set_cancel(Cancel::with_deadline(
+        Instant::now() + Duration::from_millis(100),
+    ));
+    solution.analyse();
+    assert!(!solution.answer_part1().is_solved());
+}
+
+#[test]
+fn render() {
+    let mut solution =
+        Solution::parse(include_str!("../../inputs/2020/input.day11.small")).unwrap();
+    solution.analyse();
+    assert_eq!(
+        solution.render(Part::One).unwrap(),
+        "#.#L.L#.##\n\
+         #LLL#LL.L#\n\
+         L.#.L..#..\n\
+         #L##.##.L#\n\
+         #.#L.LL.LL\n\
+         #.#L#L#.##\n\
+         ..L.L.....\n\
+         #L#L##L#L#\n\
+         #.LLLLLL.L\n\
+         #.#L#L#.##\n"
+    );
+}
@@ -0,0 +1,22 @@
+use crate::Instruction;
+use rand::Rng;
+use std::io::Write;
+
+/// `size` navigation instructions, turning by a quarter, half or three quarter turn.
+pub fn input(size: usize, rng: &mut impl Rng, out: &mut dyn Write) -> anyhow::Result<()> {
+    for _ in 0..size {
+        let amount = rng.gen_range(1..100);
+        let degrees = 90 * rng.gen_range(1..=3);
+        let instruction = match rng.gen_range(0..10) {
+            0 => Instruction::North(amount),
+            1 => Instruction::South(amount),
+            2 => Instruction::East(amount),
+            3 => Instruction::West(amount),
+            4 => Instruction::Left(degrees),
+            5 => Instruction::Right(degrees),
+            _ => Instruction::Forward(amount),
+        };
+        writeln!(out, "{}", instruction)?;
+    }
+    Ok(())
+}
@@ -1,23 +1,13 @@
 use log::debug;
+use puzzle::{check_lines, parse_at, Answer, ParseError, Puzzle};
+use rand::rngs::StdRng;
 use regex::Regex;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
-use std::num::ParseIntError;
+use std::fmt::Display;
+use std::io::{BufRead, Write};
 use std::ops::AddAssign;
 use std::str::FromStr;
 
-pub fn load(filename: &str) -> anyhow::Result<Solution> {
-    let file = File::open(filename)?;
-
-    let reader = BufReader::new(file);
-    let mut solution = Solution::new();
-
-    for line in reader.lines() {
-        let line = line?;
-        solution += Instruction::from_str(&line)?;
-    }
-    Ok(solution)
-}
+mod generate;
 
 #[derive(Debug, Default)]
 pub struct Solution {
@@ -32,19 +22,6 @@ impl Solution {
         Self::default()
     }
 
-    pub fn analyse(&mut self) {
-        self.answer_part1 = self.analyse_part1();
-        self.answer_part2 = self.analyse_part2();
-    }
-
-    pub fn answer_part1(&self) -> Option<i64> {
-        self.answer_part1
-    }
-
-    pub fn answer_part2(&self) -> Option<i64> {
-        self.answer_part2
-    }
-
     fn analyse_part1(&self) -> Option<i64> {
         use Instruction::*;
 
@@ -121,13 +98,51 @@ impl Solution {
     }
 }
 
+impl Puzzle for Solution {
+    const YEAR: u32 = 2020;
+    const DAY: u32 = 12;
+    const TITLE: &'static str = "Rain Risk";
+    const VERSION: &'static str = env!("CARGO_PKG_VERSION");
+
+    fn from_reader(reader: impl BufRead) -> anyhow::Result<Self> {
+        let mut solution = Solution::new();
+
+        for (idx, line) in reader.lines().enumerate() {
+            let line = line?;
+            solution += Instruction::from_str(&line).map_err(|e| e.at_line(idx + 1))?;
+        }
+        Ok(solution)
+    }
+
+    fn check(input: &str) -> Vec<ParseError> {
+        check_lines(input, |line| Instruction::from_str(line).map(drop))
+    }
+
+    fn generate(size: usize, rng: &mut StdRng, out: &mut dyn Write) -> anyhow::Result<()> {
+        generate::input(size, rng, out)
+    }
+
+    fn analyse(&mut self) {
+        self.answer_part1 = self.analyse_part1();
+        self.answer_part2 = self.analyse_part2();
+    }
+
+    fn answer_part1(&self) -> Answer {
+        self.answer_part1.into()
+    }
+
+    fn answer_part2(&self) -> Answer {
+        self.answer_part2.into()
+    }
+}
+
 impl AddAssign<Instruction> for Solution {
     fn add_assign(&mut self, rhs: Instruction) {
         self.instructions.push(rhs);
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 enum Instruction {
     North(usize),
     South(usize),
@@ -139,14 +154,25 @@ enum Instruction {
 }
 
 impl FromStr for Instruction {
-    type Err = ParseIntError;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let re = Regex::new(r"^(?P<mode>.)(?P<amount>\d+)$").unwrap();
 
-        let capt = re.captures(s).unwrap();
-        let amount = capt.name("amount").unwrap().as_str().parse().unwrap();
-        let instruction = match capt.name("mode").unwrap().as_str() {
+        let capt = re
+            .captures(s)
+            .ok_or_else(|| ParseError::new("expected '<action><value>'", s, 0))?;
+        let amount = capt.name("amount").unwrap();
+        let amount = parse_at(amount.as_str(), amount.start())?;
+        let mode = capt.name("mode").unwrap().as_str();
+        if (mode == "L" || mode == "R") && amount % 90 != 0 {
+            return Err(ParseError::new(
+                "expected a multiple of 90 degrees",
+                &capt["amount"],
+                1,
+            ));
+        }
+        let instruction = match mode {
             "N" => Self::North(amount),
             "S" => Self::South(amount),
             "E" => Self::East(amount),
@@ -154,9 +180,55 @@ impl FromStr for Instruction {
             "L" => Self::Left(amount),
             "R" => Self::Right(amount),
             "F" => Self::Forward(amount),
-            _ => unreachable!(),
+            mode => {
+                return Err(ParseError::new(
+                    "expected one of 'N', 'S', 'E', 'W', 'L', 'R' or 'F'",
+                    mode,
+                    0,
+                ))
+            }
         };
 
         Ok(instruction)
     }
 }
+
+impl Display for Instruction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::North(amount) => write!(f, "N{}", amount),
+            Self::South(amount) => write!(f, "S{}", amount),
+            Self::East(amount) => write!(f, "E{}", amount),
+            Self::West(amount) => write!(f, "W{}", amount),
+            Self::Left(amount) => write!(f, "L{}", amount),
+            Self::Right(amount) => write!(f, "R{}", amount),
+            Self::Forward(amount) => write!(f, "F{}", amount),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn instruction() -> impl Strategy<Value = Instruction> {
+        let degrees = (0..usize::MAX / 90).prop_map(|turns| turns * 90);
+        prop_oneof![
+            any::<usize>().prop_map(Instruction::North),
+            any::<usize>().prop_map(Instruction::South),
+            any::<usize>().prop_map(Instruction::East),
+            any::<usize>().prop_map(Instruction::West),
+            degrees.clone().prop_map(Instruction::Left),
+            degrees.prop_map(Instruction::Right),
+            any::<usize>().prop_map(Instruction::Forward),
+        ]
+    }
+
+    proptest! {
+        #[test]
+        fn display_round_trip(instruction in instruction()) {
+            prop_assert_eq!(Instruction::from_str(&instruction.to_string()).unwrap(), instruction);
+        }
+    }
+}
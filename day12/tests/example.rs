@@ -0,0 +1,11 @@
+use day12::Solution;
+use puzzle::{Answer, Puzzle};
+
+#[test]
+fn example() {
+    let mut solution =
+        Solution::parse(include_str!("../../inputs/2020/input.day12.small")).unwrap();
+    solution.analyse();
+    assert_eq!(solution.answer_part1(), Answer::Integer(25));
+    assert_eq!(solution.answer_part2(), Answer::Integer(286));
+}
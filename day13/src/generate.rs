@@ -0,0 +1,54 @@
+use rand::seq::SliceRandom;
+use rand::Rng;
+use std::io::Write;
+
+/// A timetable of `size` entries, a fifth of them buses, starting with one. Every bus has a
+/// distinct prime id, so that their departures eventually line up.
+pub fn input(size: usize, rng: &mut impl Rng, out: &mut dyn Write) -> anyhow::Result<()> {
+    let size = size.max(1);
+    let buses = size / 5 + 1;
+    let mut ids = primes(4 * buses + 3);
+    // Skip 2, 3 and 5, which are too small to be interesting
+    ids.drain(..3);
+    ids.shuffle(rng);
+    let mut entries = vec![None; size];
+    entries[0] = ids.pop();
+    let mut slots: Vec<usize> = (1..size).collect();
+    slots.shuffle(rng);
+    for slot in slots.into_iter().take(buses - 1) {
+        entries[slot] = ids.pop();
+    }
+
+    writeln!(out, "{}", rng.gen_range(100_000..1_000_000))?;
+    let entries: Vec<String> = entries
+        .into_iter()
+        .map(|entry| match entry {
+            Some(id) => id.to_string(),
+            None => "x".to_string(),
+        })
+        .collect();
+    writeln!(out, "{}", entries.join(","))?;
+    Ok(())
+}
+
+/// The first `count` primes.
+fn primes(count: usize) -> Vec<u64> {
+    let mut limit = 64;
+    loop {
+        let mut composite = vec![false; limit];
+        let mut primes = Vec::new();
+        for n in 2..limit {
+            if composite[n] {
+                continue;
+            }
+            primes.push(n as u64);
+            if primes.len() == count {
+                return primes;
+            }
+            for multiple in (n * n..limit).step_by(n) {
+                composite[multiple] = true;
+            }
+        }
+        limit *= 2;
+    }
+}
@@ -1,35 +1,35 @@
 use log::debug;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
-
-pub fn load(filename: &str) -> anyhow::Result<Solution> {
-    let file = File::open(filename)?;
-
-    let mut reader = BufReader::new(file);
-    let mut solution = Solution::new();
-
-    let mut line = String::new();
-    reader.read_line(&mut line)?;
-    solution.set_earliest_departure(line.trim().parse()?);
-
-    let mut line = String::new();
-    reader.read_line(&mut line)?;
-    debug!("'{}'", line);
-    line.trim()
-        .split(',')
-        .map(|s| s.parse::<i64>().ok())
-        .for_each(|v| solution.add_bus(v));
-
-    Ok(solution)
+use num_bigint::BigInt;
+use puzzle::{parse_at, Answer, Cancel, ParseError, Puzzle};
+use rand::rngs::StdRng;
+use std::io::{BufRead, Write};
+
+mod generate;
+
+/// Each bus in a comma-separated list, where `x` is out of service; ids must be positive.
+fn buses(line: &str) -> impl Iterator<Item = Result<Option<i64>, ParseError>> + '_ {
+    let mut offset = 0;
+    line.split(',').map(move |text| {
+        let start = offset;
+        offset += text.len() + 1;
+        match text {
+            "x" => Ok(None),
+            _ => match parse_at(text, start)? {
+                id if id > 0 => Ok(Some(id)),
+                _ => Err(ParseError::new("expected a positive bus id", text, start)),
+            },
+        }
+    })
 }
 
 #[derive(Debug, Default)]
 pub struct Solution {
     answer_part1: Option<i64>,
-    answer_part2: Option<i64>,
+    answer_part2: Option<BigInt>,
 
     earliest_departure: i64,
     buses: Vec<Option<i64>>,
+    cancel: Cancel,
 }
 
 impl Solution {
@@ -37,19 +37,6 @@ impl Solution {
         Self::default()
     }
 
-    pub fn analyse(&mut self) {
-        self.answer_part1 = self.analyse_part1();
-        self.answer_part2 = self.analyse_part2();
-    }
-
-    pub fn answer_part1(&self) -> Option<i64> {
-        self.answer_part1
-    }
-
-    pub fn answer_part2(&self) -> Option<i64> {
-        self.answer_part2
-    }
-
     pub fn set_earliest_departure(&mut self, time: i64) {
         self.earliest_departure = time;
     }
@@ -61,8 +48,8 @@ impl Solution {
     fn analyse_part1(&self) -> Option<i64> {
         let mut best = None;
         for bus in self.buses.iter().flatten() {
-            let departure = (self.earliest_departure / bus) + 1;
-            let departure = departure * bus;
+            let departure = (self.earliest_departure / bus).checked_add(1)?;
+            let departure = departure.checked_mul(*bus)?;
             let wait = departure - self.earliest_departure;
             debug!("{} {} vs {}", bus, wait, self.earliest_departure);
             match best {
@@ -71,43 +58,109 @@ impl Solution {
             }
         }
         debug!("{:?}", best);
-        best.map(|(bus, wait)| bus * wait)
+        best.and_then(|(bus, wait)| bus.checked_mul(wait))
     }
 
-    fn analyse_part2(&self) -> Option<i64> {
-        let mut t = 0_i64;
-        loop {
-            let (complete, next_increment) = self.part(0, t);
-
-            if complete {
-                break;
+    /// The earliest time at which each bus departs its offset in the list after the first.
+    ///
+    /// Sieves one bus at a time: once a time suits the buses so far, only multiples of their
+    /// periods (the product of their ids) keep suiting them. That product soon outgrows an `i64`.
+    ///
+    /// No time suits buses whose ids share a factor unless their offsets agree, and then the
+    /// sieve never ends; it only gives up when cancelled.
+    fn analyse_part2(&self) -> Option<BigInt> {
+        let mut t = BigInt::ZERO;
+        let mut step = BigInt::from(1);
+        for (offset, bus) in self.buses.iter().enumerate() {
+            let bus = match bus {
+                Some(bus) => BigInt::from(*bus),
+                None => continue,
+            };
+            while (&t + offset) % &bus != BigInt::ZERO {
+                if self.cancel.is_cancelled() {
+                    return None;
+                }
+                t += &step;
             }
-            t += next_increment;
-            debug!("t: {}", t);
+            debug!("match bus {} = {} @ {}", offset, bus, t);
+            step *= bus;
         }
         debug!("Complete @ {}", t);
         Some(t)
     }
+}
 
-    fn part(&self, bus_idx: usize, t: i64) -> (bool, i64) {
-        match &self.buses.get(bus_idx) {
-            None => (true, 0),
-            Some(bus) => match bus {
-                Some(bus) => {
-                    if t >= (bus_idx as i64) && (t + bus_idx as i64) % bus == 0 {
-                        debug!("match bus {} = {}", bus_idx, bus);
-                        let r = self.part(bus_idx + 1, t);
-                        match r {
-                            (true, _) => r,
-                            (false, v) => (false, v * bus),
-                        }
-                    } else {
-                        debug!("miss {} {}", bus_idx, bus);
-                        (false, 1)
-                    }
-                }
-                None => self.part(bus_idx + 1, t),
-            },
+impl Puzzle for Solution {
+    const YEAR: u32 = 2020;
+    const DAY: u32 = 13;
+    const TITLE: &'static str = "Shuttle Search";
+    const VERSION: &'static str = env!("CARGO_PKG_VERSION");
+
+    fn from_reader(mut reader: impl BufRead) -> anyhow::Result<Self> {
+        let mut solution = Solution::new();
+
+        let mut line = String::new();
+        reader.read_line(&mut line)?;
+        let earliest_departure = parse_at(line.trim(), 0).map_err(|e| e.at_line(1))?;
+        solution.set_earliest_departure(earliest_departure);
+
+        let mut line = String::new();
+        reader.read_line(&mut line)?;
+        debug!("'{}'", line);
+        for bus in buses(line.trim()) {
+            solution.add_bus(bus.map_err(|e| e.at_line(2))?);
         }
+
+        Ok(solution)
+    }
+
+    fn check(input: &str) -> Vec<ParseError> {
+        let mut problems = Vec::new();
+        let mut lines = input.lines();
+        match lines.next() {
+            Some(line) => {
+                problems.extend(parse_at::<i64>(line.trim(), 0).err().map(|e| e.at_line(1)))
+            }
+            None => {
+                problems.push(ParseError::new("expected the earliest departure", "", 0).at_line(1))
+            }
+        }
+        match lines.next() {
+            Some(line) => problems.extend(
+                buses(line.trim())
+                    .filter_map(Result::err)
+                    .map(|e| e.at_line(2)),
+            ),
+            None => problems.push(ParseError::new("expected a list of buses", "", 0).at_line(2)),
+        }
+        for (idx, line) in lines.enumerate() {
+            if !line.trim().is_empty() {
+                problems.push(
+                    ParseError::new("unexpected line after the buses", line, 0).at_line(idx + 3),
+                );
+            }
+        }
+        problems
+    }
+
+    fn generate(size: usize, rng: &mut StdRng, out: &mut dyn Write) -> anyhow::Result<()> {
+        generate::input(size, rng, out)
+    }
+
+    fn set_cancel(&mut self, cancel: Cancel) {
+        self.cancel = cancel;
+    }
+
+    fn analyse(&mut self) {
+        self.answer_part1 = self.analyse_part1();
+        self.answer_part2 = self.analyse_part2();
+    }
+
+    fn answer_part1(&self) -> Answer {
+        self.answer_part1.into()
+    }
+
+    fn answer_part2(&self) -> Answer {
+        self.answer_part2.clone().into()
     }
 }
@@ -0,0 +1,48 @@
+use day13::Solution;
+use num_bigint::BigInt;
+use puzzle::{Answer, Cancel, Puzzle};
+use std::time::{Duration, Instant};
+
+#[test]
+fn example() {
+    let mut solution =
+        Solution::parse(include_str!("../../inputs/2020/input.day13.small")).unwrap();
+    solution.analyse();
+    assert_eq!(solution.answer_part1(), Answer::Integer(295));
+    assert_eq!(
+        solution.answer_part2(),
+        Answer::BigInteger(BigInt::from(1068781))
+    );
+}
+
+#[test]
+fn unsatisfiable() {
+    let mut solution =
+        Solution::parse(include_str!("../../inputs/2020/input.day13.unsatisfiable")).unwrap();
+    solution.set_cancel(Cancel::with_deadline(
+        Instant::now() + Duration::from_millis(100),
+    ));
+    solution.analyse();
+    assert_eq!(solution.answer_part1(), Answer::Integer(4));
+    assert!(!solution.answer_part2().is_solved());
+}
+
+#[test]
+fn check() {
+    let problems = Solution::check("939\n7,13,0,x,y\n");
+    let columns: Vec<_> = problems.iter().map(|e| (e.line, e.column)).collect();
+    assert_eq!(columns, vec![(Some(2), 6), (Some(2), 10)]);
+    let problems = Solution::check("939");
+    assert_eq!(problems[0].message, "expected a list of buses");
+    assert_eq!(
+        Solution::check(include_str!("../../inputs/2020/input.day13.small")),
+        vec![]
+    );
+}
+
+#[test]
+fn overflow_is_unsolved() {
+    let mut solution = Solution::parse("9223372036854775807\n2\n").unwrap();
+    solution.analyse();
+    assert!(!solution.answer_part1().is_solved());
+}
@@ -0,0 +1,31 @@
+use crate::Instruction;
+use rand::seq::index;
+use rand::Rng;
+use std::io::Write;
+
+/// `size` instructions: masks with up to nine floating bits, each followed by a few writes.
+pub fn input(size: usize, rng: &mut impl Rng, out: &mut dyn Write) -> anyhow::Result<()> {
+    let mut written = 0;
+    while written < size {
+        let mut mask: Vec<char> = (0..36)
+            .map(|_| match rng.gen_bool(0.5) {
+                true => '1',
+                false => '0',
+            })
+            .collect();
+        // Floating bits multiply the addresses written in part 2, so keep them few
+        let floating = rng.gen_range(0..=9);
+        for idx in index::sample(rng, 36, floating) {
+            mask[idx] = 'X';
+        }
+        writeln!(out, "{}", Instruction::Mask(mask.into_iter().collect()))?;
+        written += 1;
+
+        for _ in 0..rng.gen_range(1..=8).min(size - written) {
+            let set = Instruction::Set(rng.gen_range(0..1 << 16), rng.gen_range(0..1 << 36));
+            writeln!(out, "{}", set)?;
+            written += 1;
+        }
+    }
+    Ok(())
+}
@@ -1,91 +1,106 @@
 use log::debug;
+use num_bigint::BigInt;
+use puzzle::{check_lines, parse_at, Answer, ParseError, Puzzle};
+use rand::rngs::StdRng;
 use regex::Regex;
 use std::collections::HashMap;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
-use std::num::ParseIntError;
+use std::fmt::Display;
+use std::io::{BufRead, Write};
 use std::ops::AddAssign;
 use std::str::FromStr;
 
-pub fn load(filename: &str) -> anyhow::Result<Solution> {
-    let file = File::open(filename)?;
-
-    let reader = BufReader::new(file);
-    let mut solution = Solution::new();
-
-    for line in reader.lines() {
-        let line = line?;
-        solution += Instruction::from_str(&line)?;
-    }
-    Ok(solution)
-}
+mod generate;
 
 #[derive(Debug, Default)]
 pub struct Solution {
     instructions: Vec<Instruction>,
 
-    answer_part1: Option<i64>,
-    answer_part2: Option<i64>,
+    answer_part1: Option<BigInt>,
+    answer_part2: Option<BigInt>,
 }
 
 impl Solution {
     fn new() -> Self {
         Self::default()
     }
+}
+
+impl Puzzle for Solution {
+    const YEAR: u32 = 2020;
+    const DAY: u32 = 14;
+    const TITLE: &'static str = "Docking Data";
+    const VERSION: &'static str = env!("CARGO_PKG_VERSION");
 
-    pub fn analyse(&mut self) {
+    fn from_reader(reader: impl BufRead) -> anyhow::Result<Self> {
+        let mut solution = Solution::new();
+
+        let mut masked = false;
+        for (idx, line) in reader.lines().enumerate() {
+            let line = line?;
+            let instruction =
+                Instruction::check(&line, &mut masked).map_err(|e| e.at_line(idx + 1))?;
+            solution += instruction;
+        }
+        Ok(solution)
+    }
+
+    fn check(input: &str) -> Vec<ParseError> {
+        let mut masked = false;
+        check_lines(input, |line| {
+            Instruction::check(line, &mut masked).map(drop)
+        })
+    }
+
+    fn generate(size: usize, rng: &mut StdRng, out: &mut dyn Write) -> anyhow::Result<()> {
+        generate::input(size, rng, out)
+    }
+
+    fn analyse(&mut self) {
         self.answer_part1 = self.analyse_part1();
         self.answer_part2 = self.analyse_part2();
     }
 
-    pub fn answer_part1(&self) -> Option<i64> {
-        self.answer_part1
+    fn answer_part1(&self) -> Answer {
+        self.answer_part1.clone().into()
     }
 
-    pub fn answer_part2(&self) -> Option<i64> {
-        self.answer_part2
+    fn answer_part2(&self) -> Answer {
+        self.answer_part2.clone().into()
     }
 }
 
 impl Solution {
-    fn analyse_part1(&self) -> Option<i64> {
+    fn analyse_part1(&self) -> Option<BigInt> {
         let mut mask = None;
         let mut values = HashMap::new();
         for instruction in &self.instructions {
             match instruction {
                 Instruction::Mask(m) => mask = Some(m),
                 Instruction::Set(addr, value) => {
-                    let value = Instruction::apply_mask_part1(mask.unwrap(), *value);
+                    let value = Instruction::apply_mask_part1(mask?, *value);
                     values.insert(addr, value);
                 }
             };
         }
-        let mut total = 0_i64;
-        for (_addr, value) in values {
-            total += value;
-        }
-        Some(total)
+        // Every address can hold a 36-bit value, so the total can outgrow an `i64`
+        Some(values.into_values().map(BigInt::from).sum())
     }
 
-    fn analyse_part2(&self) -> Option<i64> {
+    fn analyse_part2(&self) -> Option<BigInt> {
         let mut mask = None;
         let mut values = HashMap::new();
         for instruction in &self.instructions {
             match instruction {
                 Instruction::Mask(m) => mask = Some(m),
                 Instruction::Set(addr, value) => {
-                    let addresses = Instruction::apply_mask_part2(mask.unwrap(), *addr as i64);
+                    let addresses = Instruction::apply_mask_part2(mask?, *addr as i64);
                     for address in addresses {
                         values.insert(address, value);
                     }
                 }
             }
         }
-        let mut total = 0_i64;
-        for (_addr, value) in values {
-            total += value;
-        }
-        Some(total)
+        Some(values.into_values().map(|&value| BigInt::from(value)).sum())
     }
 }
 
@@ -95,13 +110,31 @@ impl AddAssign<Instruction> for Solution {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 enum Instruction {
     Mask(String),
     Set(u64, i64),
 }
 
 impl Instruction {
+    /// Parse an instruction, which can only set memory once a mask has been given; `masked`
+    /// tracks whether one has.
+    fn check(line: &str, masked: &mut bool) -> Result<Self, ParseError> {
+        let instruction = Self::from_str(line)?;
+        match instruction {
+            Self::Mask(_) => *masked = true,
+            Self::Set(..) if !*masked => {
+                return Err(ParseError::new(
+                    "expected a mask before setting memory",
+                    line,
+                    0,
+                ))
+            }
+            Self::Set(..) => {}
+        }
+        Ok(instruction)
+    }
+
     fn apply_mask_part1(mask: &str, value: i64) -> i64 {
         let mut value = value;
         for (idx, c) in mask.chars().rev().enumerate() {
@@ -135,7 +168,7 @@ impl Instruction {
                         values.push(0);
                         values.push(v);
                     } else {
-                        let mut next_values = vec![true, false]
+                        let mut next_values = [true, false]
                             .iter()
                             .flat_map(|b| {
                                 values.iter().map(move |value: &i64| match b {
@@ -173,25 +206,52 @@ impl Instruction {
 }
 
 impl FromStr for Instruction {
-    type Err = ParseIntError;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        if let Some(s) = s.strip_prefix("mask = ") {
-            Ok(Self::Mask(s.to_string()))
+        const MASK: &str = "mask = ";
+
+        if let Some(mask) = s.strip_prefix(MASK) {
+            if mask.len() != 36 {
+                return Err(ParseError::new("expected 36 mask bits", mask, MASK.len()));
+            }
+            if let Some(idx) = mask.find(|c| !matches!(c, 'X' | '0' | '1')) {
+                let c = mask[idx..].chars().next().unwrap_or_default();
+                return Err(ParseError::new(
+                    "expected 'X', '0' or '1'",
+                    &c.to_string(),
+                    MASK.len() + idx,
+                ));
+            }
+            Ok(Self::Mask(mask.to_string()))
         } else {
             let re = Regex::new(r"^mem\[(?P<addr>\d+)\] = (?P<value>\d+)$").unwrap();
-            let capt = re.captures(s).unwrap();
-            let addr = capt.name("addr").unwrap().as_str().parse::<u64>().unwrap();
-            let value = capt.name("value").unwrap().as_str().parse::<i64>().unwrap();
+            let capt = re.captures(s).ok_or_else(|| {
+                ParseError::new("expected 'mask = <mask>' or 'mem[<addr>] = <value>'", s, 0)
+            })?;
+            let addr = capt.name("addr").unwrap();
+            let addr = parse_at(addr.as_str(), addr.start())?;
+            let value = capt.name("value").unwrap();
+            let value = parse_at(value.as_str(), value.start())?;
             Ok(Self::Set(addr, value))
         }
     }
 }
 
+impl Display for Instruction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Mask(mask) => write!(f, "mask = {}", mask),
+            Self::Set(addr, value) => write!(f, "mem[{}] = {}", addr, value),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     // Note this useful idiom: importing names from outer (for mod tests) scope.
     use super::*;
+    use proptest::prelude::*;
 
     #[ctor::ctor]
     fn init() {
@@ -209,4 +269,51 @@ mod tests {
         let result = Instruction::apply_mask_part2("000000000000000000000000000000X1001X", 42);
         assert_eq!(result, vec![59, 58, 27, 26]);
     }
+
+    #[test]
+    fn parse_example() {
+        let solution = Solution::parse(
+            "mask = XXXXXXXXXXXXXXXXXXXXXXXXXXXXX1XXXX0X\nmem[8] = 11\nmem[7] = 101\nmem[8] = 0\n",
+        )
+        .unwrap();
+        assert_eq!(solution.analyse_part1(), Some(BigInt::from(165)));
+    }
+
+    #[test]
+    fn parse_invalid_mask() {
+        let result =
+            Instruction::from_str("mask = XXXXXXXXXXXXXXXXXXXXXXXXXXXXX1XXXX2X").unwrap_err();
+        assert_eq!(result.column, 42);
+        assert_eq!(result.text, "2");
+
+        // 36 bytes, but not 36 bits
+        let result =
+            Instruction::from_str("mask = XXXXXXXXXXXXXXXXXXXXXXXXXXXXX1XXé0X").unwrap_err();
+        assert_eq!(result.column, 40);
+        assert_eq!(result.text, "é");
+    }
+
+    #[test]
+    fn mask_first() {
+        let problems = Solution::check(
+            "mem[8] = 11\nmask = XXXXXXXXXXXXXXXXXXXXXXXXXXXXX1XXXX0X\nmem[7] = 101\n",
+        );
+        let lines: Vec<_> = problems.iter().map(|e| e.line).collect();
+        assert_eq!(lines, vec![Some(1)]);
+        assert!(Solution::parse("mem[8] = 11\n").is_err());
+    }
+
+    fn instruction() -> impl Strategy<Value = Instruction> {
+        prop_oneof![
+            "[X01]{36}".prop_map(Instruction::Mask),
+            (any::<u64>(), 0..=i64::MAX).prop_map(|(addr, value)| Instruction::Set(addr, value)),
+        ]
+    }
+
+    proptest! {
+        #[test]
+        fn display_round_trip(instruction in instruction()) {
+            prop_assert_eq!(Instruction::from_str(&instruction.to_string()).unwrap(), instruction);
+        }
+    }
 }
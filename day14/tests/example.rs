@@ -0,0 +1,18 @@
+use day14::Solution;
+use num_bigint::BigInt;
+use puzzle::{Answer, Puzzle};
+
+#[test]
+fn example() {
+    let mut solution =
+        Solution::parse(include_str!("../../inputs/2020/input.day14.small")).unwrap();
+    solution.analyse();
+    assert_eq!(
+        solution.answer_part1(),
+        Answer::BigInteger(BigInt::from(51))
+    );
+    assert_eq!(
+        solution.answer_part2(),
+        Answer::BigInteger(BigInt::from(208))
+    );
+}
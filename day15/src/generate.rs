@@ -0,0 +1,14 @@
+use rand::seq::index;
+use rand::Rng;
+use std::io::Write;
+
+/// `size` distinct starting numbers.
+pub fn input(size: usize, rng: &mut impl Rng, out: &mut dyn Write) -> anyhow::Result<()> {
+    let size = size.max(1);
+    let numbers: Vec<String> = index::sample(rng, 4 * size, size)
+        .into_iter()
+        .map(|number| number.to_string())
+        .collect();
+    writeln!(out, "{}", numbers.join(","))?;
+    Ok(())
+}
@@ -1,13 +1,11 @@
-use log::debug;
+use puzzle::{parse_at, Answer, ParseError, Puzzle};
+use rand::rngs::StdRng;
 use std::collections::HashMap;
-use std::num::ParseIntError;
+use std::io::{BufRead, Write};
 use std::str::FromStr;
+use tracing::debug_span;
 
-use anyhow::Context;
-
-pub fn load(payload: &str) -> anyhow::Result<Solution> {
-    Solution::from_str(payload).context("Failed to parse")
-}
+mod generate;
 
 #[derive(Debug, Default)]
 pub struct Solution {
@@ -42,16 +40,32 @@ impl Vec2 {
         }
     }
 }
-impl Solution {
-    pub fn analyse(&mut self) {}
+impl Puzzle for Solution {
+    const YEAR: u32 = 2020;
+    const DAY: u32 = 15;
+    const TITLE: &'static str = "Rambunctious Recitation";
+    const VERSION: &'static str = env!("CARGO_PKG_VERSION");
+
+    fn from_reader(mut reader: impl BufRead) -> anyhow::Result<Self> {
+        let mut payload = String::new();
+        reader.read_to_string(&mut payload)?;
+        let solution = Solution::from_str(&payload).map_err(|e| e.at_line(1))?;
+        Ok(solution)
+    }
+
+    fn generate(size: usize, rng: &mut StdRng, out: &mut dyn Write) -> anyhow::Result<()> {
+        generate::input(size, rng, out)
+    }
+
+    fn analyse(&mut self) {}
 
-    pub fn answer_part1(&self) -> Option<i64> {
+    fn answer_part1(&self) -> Answer {
+        let _game = debug_span!("game", turns = 2020).entered();
         let mut last = None;
         let mut seen = HashMap::new();
         for turn in 1..=2020 {
             if let Some(v) = self.input.get(turn - 1) {
                 let v = *v;
-                debug!("{}: {}", turn, v);
                 last = Some(v);
                 seen.entry(v).or_insert_with(Vec2::new).push(turn);
                 continue;
@@ -66,21 +80,20 @@ impl Solution {
                     let prev = last_seeings.last2.unwrap();
                     v = (last - prev) as i64;
                 }
-                debug!("{}: {} {:?}", turn, v, last_seeings);
                 last = Some(v);
                 seen.entry(v).or_insert_with(Vec2::new).push(turn);
             }
         }
-        last
+        last.into()
     }
 
-    pub fn answer_part2(&self) -> Option<i64> {
+    fn answer_part2(&self) -> Answer {
+        let _game = debug_span!("game", turns = 30000000).entered();
         let mut last = None;
         let mut seen = HashMap::new();
         for turn in 1..=30000000 {
             if let Some(v) = self.input.get(turn - 1) {
                 let v = *v;
-                debug!("{}: {}", turn, v);
                 last = Some(v);
                 seen.entry(v).or_insert_with(Vec2::new).push(turn);
                 continue;
@@ -95,23 +108,23 @@ impl Solution {
                     let prev = last_seeings.last2.unwrap();
                     v = (last - prev) as i64;
                 }
-                debug!("{}: {} {:?}", turn, v, last_seeings);
                 last = Some(v);
                 seen.entry(v).or_insert_with(Vec2::new).push(turn);
             }
         }
-        last
+        last.into()
     }
 }
 
 impl FromStr for Solution {
-    type Err = ParseIntError;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let mut input = Vec::new();
+        let mut offset = 0;
         for part in s.trim().split(',') {
-            let part = part.parse().unwrap();
-            input.push(part);
+            input.push(parse_at(part, offset)?);
+            offset += part.len() + 1;
         }
         Ok(Self { input })
     }
@@ -0,0 +1,39 @@
+use day15::Solution;
+use puzzle::{Answer, Puzzle};
+
+#[test]
+fn example() {
+    let mut solution =
+        Solution::parse(include_str!("../../inputs/2020/input.day15.small")).unwrap();
+    solution.analyse();
+    assert_eq!(solution.answer_part1(), Answer::Integer(436));
+}
+
+#[test]
+fn other_starting_numbers() {
+    for (input, expected) in [
+        ("1,3,2", 1),
+        ("2,1,3", 10),
+        ("1,2,3", 27),
+        ("2,3,1", 78),
+        ("3,2,1", 438),
+        ("3,1,2", 1836),
+    ] {
+        let solution = Solution::parse(input).unwrap();
+        assert_eq!(
+            solution.answer_part1(),
+            Answer::Integer(expected),
+            "{}",
+            input
+        );
+    }
+}
+
+#[test]
+#[ignore = "plays 30 million turns, which takes a while without optimisations"]
+fn example_part2() {
+    let mut solution =
+        Solution::parse(include_str!("../../inputs/2020/input.day15.small")).unwrap();
+    solution.analyse();
+    assert_eq!(solution.answer_part2(), Answer::Integer(175594));
+}
@@ -0,0 +1,83 @@
+use crate::{Rule, Ticket};
+use rand::seq::SliceRandom;
+use rand::Rng;
+use std::io::Write;
+
+const FIELDS: [&str; 20] = [
+    "departure location",
+    "departure station",
+    "departure platform",
+    "departure track",
+    "departure date",
+    "departure time",
+    "arrival location",
+    "arrival station",
+    "arrival platform",
+    "arrival track",
+    "class",
+    "duration",
+    "price",
+    "route",
+    "row",
+    "seat",
+    "train",
+    "type",
+    "wagon",
+    "zone",
+];
+
+/// Twenty field rules, your ticket and `size` nearby tickets, a fifth of them with an invalid
+/// value.
+///
+/// Values fall into bands of fifty, one per field. Each field accepts its own band and those
+/// above it, and every value is drawn from its field's band or above, with the first valid nearby
+/// ticket (like your ticket) keeping to its own band. So only one field fits the position
+/// holding the values of the lowest band, then one more once that is known, and so on.
+pub fn input(size: usize, rng: &mut impl Rng, out: &mut dyn Write) -> anyhow::Result<()> {
+    let bands = FIELDS.len() as i64;
+    let mut positions: Vec<i64> = (0..bands).collect();
+    positions.shuffle(rng);
+
+    for (field, name) in FIELDS.iter().enumerate() {
+        let (low, high) = (50 * field as i64 + 1, 50 * bands - 1);
+        let split = rng.gen_range(low..high);
+        let rule = Rule {
+            name: name.to_string(),
+            ranges: vec![(low, split), (split + 1, high)],
+        };
+        writeln!(out, "{}", rule)?;
+    }
+
+    writeln!(out, "\nyour ticket:\n{}", ticket(&positions, true, rng))?;
+    writeln!(out, "\nnearby tickets:")?;
+    writeln!(out, "{}", ticket(&positions, true, rng))?;
+    for _ in 1..size {
+        let mut ticket = ticket(&positions, false, rng);
+        if rng.gen_bool(0.2) {
+            let value = ticket.values.choose_mut(rng).unwrap();
+            *value = match rng.gen_bool(0.1) {
+                true => 0,
+                false => rng.gen_range(50 * bands..2 * 50 * bands),
+            };
+        }
+        writeln!(out, "{}", ticket)?;
+    }
+    Ok(())
+}
+
+/// A valid ticket with the given field at each position, keeping to each field's own band if
+/// `lowest`.
+fn ticket(positions: &[i64], lowest: bool, rng: &mut impl Rng) -> Ticket {
+    let bands = positions.len() as i64;
+    let values = positions
+        .iter()
+        .map(|&field| {
+            let band = match lowest {
+                true => field,
+                false => rng.gen_range(field..bands),
+            };
+            50 * band + rng.gen_range(1..50)
+        })
+        .collect();
+    Ticket { values }
+}
@@ -1,41 +1,12 @@
 use log::debug;
+use puzzle::{parse_at, Answer, Explanation, ParseError, Part, Puzzle};
+use rand::rngs::StdRng;
 use std::collections::{HashMap, HashSet};
-use std::fs::File;
-use std::io::{BufRead, BufReader};
-use std::num::ParseIntError;
+use std::fmt::Display;
+use std::io::{BufRead, Write};
 use std::str::FromStr;
 
-pub fn load(filename: &str) -> anyhow::Result<Solution> {
-    let file = File::open(filename)?;
-
-    let reader = BufReader::new(file);
-
-    let mut solution = Solution::default();
-    let mut mode = ParseState::Initial;
-    for line in reader.lines() {
-        let line = line?;
-        let line = line.trim();
-        if line.is_empty() {
-            continue;
-        }
-        match line {
-            "your ticket:" => mode = ParseState::MyTicket,
-            "nearby tickets:" => mode = ParseState::NearTickets,
-            _ => match mode {
-                ParseState::Initial => {
-                    solution.add_rule(Rule::from_str(line)?);
-                }
-                ParseState::MyTicket => {
-                    solution.set_my_ticket(Ticket::from_str(line)?);
-                }
-                ParseState::NearTickets => {
-                    solution.add_near_ticket(Ticket::from_str(line)?);
-                }
-            },
-        }
-    }
-    Ok(solution)
-}
+mod generate;
 
 #[derive(Debug)]
 enum ParseState {
@@ -51,10 +22,117 @@ pub struct Solution {
     rules: Vec<Rule>,
 }
 
-impl Solution {
-    pub fn analyse(&mut self) {}
+impl Puzzle for Solution {
+    const YEAR: u32 = 2020;
+    const DAY: u32 = 16;
+    const TITLE: &'static str = "Ticket Translation";
+    const VERSION: &'static str = env!("CARGO_PKG_VERSION");
+
+    fn from_reader(mut reader: impl BufRead) -> anyhow::Result<Self> {
+        let mut input = String::new();
+        reader.read_to_string(&mut input)?;
+        // The sections must be complete and in order, as well as each line well formed
+        if let Some(e) = Self::check(&input).into_iter().next() {
+            return Err(e.into());
+        }
+        let mut solution = Solution::default();
+        let mut mode = ParseState::Initial;
+        for line in input.lines() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            match line {
+                "your ticket:" => mode = ParseState::MyTicket,
+                "nearby tickets:" => mode = ParseState::NearTickets,
+                _ => match mode {
+                    ParseState::Initial => {
+                        solution.add_rule(Rule::from_str(line)?);
+                    }
+                    ParseState::MyTicket => {
+                        solution.set_my_ticket(Ticket::from_str(line)?);
+                    }
+                    ParseState::NearTickets => {
+                        solution.add_near_ticket(Ticket::from_str(line)?);
+                    }
+                },
+            }
+        }
+        Ok(solution)
+    }
 
-    pub fn answer_part1(&self) -> Option<i64> {
+    /// Beyond each line parsing, the headers must come in order, there must be a single ticket of
+    /// our own, and every ticket must have a value for each field.
+    fn check(input: &str) -> Vec<ParseError> {
+        let mut problems = Vec::new();
+        let mut mode = ParseState::Initial;
+        let (mut fields, mut my_tickets) = (0, 0);
+        for (idx, line) in input.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            let problem = match (line, &mode) {
+                // Out of order headers are reported, but still start their section
+                ("your ticket:", _) => {
+                    let problem = match mode {
+                        ParseState::Initial if fields == 0 => {
+                            Some("expected the fields' rules first")
+                        }
+                        ParseState::Initial => None,
+                        _ => Some("unexpected header"),
+                    };
+                    mode = ParseState::MyTicket;
+                    problem.map(|message| ParseError::new(message, line, 0))
+                }
+                ("nearby tickets:", _) => {
+                    let problem = match mode {
+                        ParseState::Initial => Some("expected 'your ticket:' first"),
+                        ParseState::MyTicket if my_tickets == 0 => {
+                            Some("expected a ticket of our own first")
+                        }
+                        ParseState::MyTicket => None,
+                        ParseState::NearTickets => Some("unexpected header"),
+                    };
+                    mode = ParseState::NearTickets;
+                    problem.map(|message| ParseError::new(message, line, 0))
+                }
+                (_, ParseState::Initial) => {
+                    // Count a field even if its rule is malformed, to check the tickets against
+                    fields += 1;
+                    Rule::from_str(line).err()
+                }
+                (_, ParseState::MyTicket) if my_tickets > 0 => Some(ParseError::new(
+                    "expected a single ticket of our own",
+                    line,
+                    0,
+                )),
+                (_, ParseState::MyTicket) => {
+                    my_tickets += 1;
+                    check_ticket(line, fields).err()
+                }
+                (_, ParseState::NearTickets) => check_ticket(line, fields).err(),
+            };
+            problems.extend(problem.map(|e| e.at_line(idx + 1)));
+        }
+        let end = input.lines().count() + 1;
+        let missing = match mode {
+            ParseState::Initial => Some("expected 'your ticket:'"),
+            ParseState::MyTicket if my_tickets == 0 => Some("expected a ticket of our own"),
+            ParseState::MyTicket => Some("expected 'nearby tickets:'"),
+            ParseState::NearTickets => None,
+        };
+        problems.extend(missing.map(|message| ParseError::new(message, "", 0).at_line(end)));
+        problems
+    }
+
+    fn generate(size: usize, rng: &mut StdRng, out: &mut dyn Write) -> anyhow::Result<()> {
+        generate::input(size, rng, out)
+    }
+
+    fn analyse(&mut self) {}
+
+    fn answer_part1(&self) -> Answer {
         let mut invalid_values = Vec::new();
         for ticket in &self.near_tickets {
             for value in &ticket.values {
@@ -64,10 +142,72 @@ impl Solution {
             }
         }
         debug!("Invalid values: {:?}", invalid_values.len());
-        Some(invalid_values.iter().sum())
+        match invalid_values
+            .iter()
+            .try_fold(0_i64, |total, value| total.checked_add(*value))
+        {
+            Some(total) => Answer::Integer(total),
+            None => Answer::Unsolved("the error rate overflows".to_string()),
+        }
+    }
+
+    fn answer_part2(&self) -> Answer {
+        let known = self.assignment();
+        let mut total = 1_i64;
+        for (k, idx) in known
+            .into_iter()
+            .filter(|(k, _v)| k.starts_with("departure"))
+        {
+            let Some(value) = self.my_ticket.values.get(idx) else {
+                return Answer::Unsolved(format!("our ticket has no value for {}", k));
+            };
+            debug!("{}, {} = {}", k, idx, value);
+            let Some(product) = total.checked_mul(*value) else {
+                return Answer::Unsolved(
+                    "the product of the departure fields overflows".to_string(),
+                );
+            };
+            total = product;
+        }
+        Answer::Integer(total)
     }
 
-    pub fn answer_part2(&self) -> Option<i64> {
+    fn explain(&self, part: Part) -> Option<Explanation> {
+        let explanation = match part {
+            Part::One => Explanation::record([(
+                "invalid",
+                self.near_tickets
+                    .iter()
+                    .flat_map(|ticket| &ticket.values)
+                    .copied()
+                    .filter(|value| !self.valid_ticket_value(*value))
+                    .collect::<Vec<_>>()
+                    .into(),
+            )]),
+            Part::Two => {
+                let mut columns: Vec<(String, usize)> = self.assignment().into_iter().collect();
+                columns.sort_by_key(|(_, column)| *column);
+                let columns = columns
+                    .into_iter()
+                    .map(|(field, column)| {
+                        let value = *self.my_ticket.values.get(column)?;
+                        Some(Explanation::record([
+                            ("column", column.into()),
+                            ("field", field.into()),
+                            ("value", value.into()),
+                        ]))
+                    })
+                    .collect::<Option<Vec<_>>>()?;
+                Explanation::record([("columns", columns.into())])
+            }
+        };
+        Some(explanation)
+    }
+}
+
+impl Solution {
+    /// The column of each field, found by elimination across the valid nearby tickets.
+    fn assignment(&self) -> HashMap<String, usize> {
         let mut count = 0;
         let mut categories = Vec::new();
         for ticket in self
@@ -145,25 +285,9 @@ impl Solution {
             }
         }
         debug!("{:?}", known);
-        debug!("{} / {}", count, self.near_tickets.len());
-        let mut total = 1_i64;
-        for (k, idx) in known
-            .into_iter()
-            .filter(|(k, _v)| k.starts_with("departure"))
-        {
-            debug!(
-                "{}, {} = {}",
-                k,
-                idx,
-                self.my_ticket.values.get(idx).unwrap()
-            );
-            total *= self.my_ticket.values.get(idx).unwrap();
-        }
-        Some(total)
+        known
     }
-}
 
-impl Solution {
     fn add_near_ticket(&mut self, ticket: Ticket) {
         self.near_tickets.push(ticket);
     }
@@ -188,44 +312,125 @@ impl Solution {
     }
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, PartialEq)]
 struct Ticket {
     values: Vec<i64>,
 }
 
 impl FromStr for Ticket {
-    type Err = ParseIntError;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let values = s.trim().split(',').map(|v| v.parse().unwrap()).collect();
+        let mut values = Vec::new();
+        let mut offset = 0;
+        for v in s.trim().split(',') {
+            values.push(parse_at(v, offset)?);
+            offset += v.len() + 1;
+        }
 
         Ok(Self { values })
     }
 }
 
-#[derive(Debug, Default)]
+/// A ticket must have a value for each of the `fields`.
+fn check_ticket(line: &str, fields: usize) -> Result<(), ParseError> {
+    match Ticket::from_str(line)? {
+        ticket if ticket.values.len() == fields => Ok(()),
+        _ => Err(ParseError::new(
+            format!("expected {} value(s), one per field", fields),
+            line,
+            0,
+        )),
+    }
+}
+
+impl Display for Ticket {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (idx, value) in self.values.iter().enumerate() {
+            if idx > 0 {
+                write!(f, ",")?;
+            }
+            write!(f, "{}", value)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default, PartialEq)]
 struct Rule {
     name: String,
     ranges: Vec<(i64, i64)>,
 }
 
 impl FromStr for Rule {
-    type Err = ParseIntError;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let pos = s.trim().find(':').unwrap();
-        let name = (&s[..pos]).to_string();
-
-        let ranges = (&s[pos + 1..])
-            .trim()
-            .split(" or ")
-            .map(|v| {
-                v.split_once("-")
-                    .map(|(s, e)| (s.parse().unwrap(), e.parse().unwrap()))
-                    .unwrap()
-            })
-            .collect();
+        let pos = s
+            .find(':')
+            .ok_or_else(|| ParseError::new("expected '<name>: <ranges>'", s, 0))?;
+        let name = s[..pos].to_string();
+
+        let rest = &s[pos + 1..];
+        let mut ranges = Vec::new();
+        let mut offset = pos + 1 + rest.len() - rest.trim_start().len();
+        for v in rest.trim().split(" or ") {
+            let (start, end) = v
+                .split_once('-')
+                .ok_or_else(|| ParseError::new("expected '<start>-<end>'", v, offset))?;
+            ranges.push((
+                parse_at(start, offset)?,
+                parse_at(end, offset + start.len() + 1)?,
+            ));
+            offset += v.len() + 4;
+        }
 
         Ok(Self { name, ranges })
     }
 }
+
+impl Display for Rule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: ", self.name)?;
+        for (idx, (start, end)) in self.ranges.iter().enumerate() {
+            if idx > 0 {
+                write!(f, " or ")?;
+            }
+            write!(f, "{}-{}", start, end)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn ticket() -> impl Strategy<Value = Ticket> {
+        prop::collection::vec(any::<i64>(), 1..20).prop_map(|values| Ticket { values })
+    }
+
+    fn rule() -> impl Strategy<Value = Rule> {
+        let ranges = prop::collection::vec((0..=i64::MAX, 0..=i64::MAX), 1..4);
+        ("[a-z]+( [a-z]+)?", ranges).prop_map(|(name, ranges)| Rule { name, ranges })
+    }
+
+    #[test]
+    fn rule_spacing() {
+        for s in ["class:1-3 or 5-7", "class:   1-3 or 5-7"] {
+            let rule = Rule::from_str(s).unwrap();
+            assert_eq!(rule.ranges, vec![(1, 3), (5, 7)], "{:?}", s);
+        }
+        assert_eq!(Rule::from_str("class:   x-3").unwrap_err().column, 10);
+        assert_eq!(Rule::from_str("class:5").unwrap_err().column, 7);
+    }
+
+    proptest! {
+        #[test]
+        fn display_round_trip(ticket in ticket(), rule in rule()) {
+            prop_assert_eq!(Ticket::from_str(&ticket.to_string()).unwrap(), ticket);
+            prop_assert_eq!(Rule::from_str(&rule.to_string()).unwrap(), rule);
+        }
+    }
+}
@@ -0,0 +1,88 @@
+use day16::Solution;
+use puzzle::{Answer, Explanation, Part, Puzzle};
+
+#[test]
+fn example() {
+    let mut solution =
+        Solution::parse(include_str!("../../inputs/2020/input.day16.small")).unwrap();
+    solution.analyse();
+    assert_eq!(solution.answer_part1(), Answer::Integer(71));
+    // No field of the example is a departure field, so their product is empty
+    assert_eq!(solution.answer_part2(), Answer::Integer(1));
+}
+
+#[test]
+fn departures() {
+    let mut solution =
+        Solution::parse(include_str!("../../inputs/2020/input.day16.departures")).unwrap();
+    solution.analyse();
+    assert_eq!(solution.answer_part1(), Answer::Integer(0));
+    assert_eq!(solution.answer_part2(), Answer::Integer(11 * 13));
+}
+
+#[test]
+fn explain() {
+    let mut solution =
+        Solution::parse(include_str!("../../inputs/2020/input.day16.small")).unwrap();
+    solution.analyse();
+    let column = |column: i64, field: &str, value: i64| {
+        Explanation::record([
+            ("column", column.into()),
+            ("field", field.into()),
+            ("value", value.into()),
+        ])
+    };
+    assert_eq!(
+        solution.explain(Part::Two),
+        Some(Explanation::record([(
+            "columns",
+            vec![
+                column(0, "row", 7),
+                column(1, "class", 1),
+                column(2, "seat", 14)
+            ]
+            .into()
+        )]))
+    );
+}
+
+#[test]
+fn check() {
+    let problems = Solution::check("class: 1-3 or 5-7\n\nnearby tickets:\n7,3\n40,4,50\n1,x\n");
+    let lines: Vec<_> = problems
+        .iter()
+        .map(|e| (e.line, e.message.as_str()))
+        .collect();
+    assert_eq!(
+        lines,
+        vec![
+            (Some(3), "expected 'your ticket:' first"),
+            (Some(4), "expected 1 value(s), one per field"),
+            (Some(5), "expected 1 value(s), one per field"),
+            (Some(6), "invalid digit found in string"),
+        ]
+    );
+    assert_eq!(
+        Solution::check(include_str!("../../inputs/2020/input.day16.small")),
+        vec![]
+    );
+    // Parsing finds the same problems, rather than leaving part 2 without a ticket of our own
+    let e = Solution::parse("class: 1-3 or 5-7\n\nnearby tickets:\n7\n").unwrap_err();
+    assert_eq!(
+        e.to_string(),
+        "3:1: expected 'your ticket:' first: 'nearby tickets:'"
+    );
+}
+
+#[test]
+fn overflow_is_unsolved() {
+    let solution = Solution::parse(
+        "departure a: 10-10 or 3000000000000-3000000000000\n\
+         departure b: 20-20 or 4000000000000-4000000000000\n\n\
+         your ticket:\n3000000000000,4000000000000\n\n\
+         nearby tickets:\n10,20\n9223372036854775807,1\n9223372036854775807,1\n",
+    )
+    .unwrap();
+    assert!(!solution.answer_part1().is_solved());
+    assert!(!solution.answer_part2().is_solved());
+}
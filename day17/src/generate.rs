@@ -0,0 +1,16 @@
+use rand::Rng;
+use std::io::Write;
+
+/// A `size` by `size` initial slice, around half of it active.
+pub fn input(size: usize, rng: &mut impl Rng, out: &mut dyn Write) -> anyhow::Result<()> {
+    for _ in 0..size {
+        let row: String = (0..size)
+            .map(|_| match rng.gen_bool(0.45) {
+                true => '#',
+                false => '.',
+            })
+            .collect();
+        writeln!(out, "{}", row)?;
+    }
+    Ok(())
+}
@@ -1,122 +1,157 @@
-use std::collections::HashMap;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use automaton::{moore, Automaton, Point, Rules, World};
+use puzzle::{check_lines, Answer, ParseError, Part, Puzzle};
+use rand::rngs::StdRng;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashSet};
+use std::io::{BufRead, Write};
 
-pub fn load(filename: &str) -> anyhow::Result<Solution> {
-    let file = File::open(filename)?;
-
-    let reader = BufReader::new(file);
-    let mut solution = Solution::default();
-
-    for (y, line) in reader.lines().enumerate() {
-        let line = line?;
-        for (x, c) in line.chars().enumerate() {
-            solution.set(x as i64, y as i64, 0_i64, 0_i64, c);
-        }
-    }
-    Ok(solution)
-}
+mod generate;
 
 #[derive(Debug, Default)]
 pub struct Solution {
-    data: HashMap<(i64, i64, i64, i64), char>,
+    /// Active cubes in the initial slice.
+    active: Vec<(i64, i64)>,
     answer_part1: Option<i64>,
     answer_part2: Option<i64>,
 }
 
-impl Solution {
-    pub fn analyse(&mut self) {
+impl Puzzle for Solution {
+    const YEAR: u32 = 2020;
+    const DAY: u32 = 17;
+    const TITLE: &'static str = "Conway Cubes";
+    const VERSION: &'static str = env!("CARGO_PKG_VERSION");
+
+    fn from_reader(reader: impl BufRead) -> anyhow::Result<Self> {
+        let mut solution = Solution::default();
+
+        for (y, line) in reader.lines().enumerate() {
+            let line = line?;
+            let active = active(&line).map_err(|e| e.at_line(y + 1))?;
+            solution
+                .active
+                .extend(active.into_iter().map(|x| (x, y as i64)));
+        }
+        Ok(solution)
+    }
+
+    fn check(input: &str) -> Vec<ParseError> {
+        check_lines(input, |line| active(line).map(drop))
+    }
+
+    fn generate(size: usize, rng: &mut StdRng, out: &mut dyn Write) -> anyhow::Result<()> {
+        generate::input(size, rng, out)
+    }
+
+    fn analyse(&mut self) {
         self.answer_part1 = self.analyse_part1();
         self.answer_part2 = self.analyse_part2();
     }
 
-    pub fn answer_part1(&self) -> Option<i64> {
-        self.answer_part1
+    fn answer_part1(&self) -> Answer {
+        self.answer_part1.into()
     }
 
-    pub fn answer_part2(&self) -> Option<i64> {
-        self.answer_part2
+    fn answer_part2(&self) -> Answer {
+        self.answer_part2.into()
+    }
+
+    /// The cubes after booting, a slice at a time.
+    fn render(&self, part: Part) -> Option<String> {
+        Some(match part {
+            Part::One => slices(&self.boot::<3>()),
+            Part::Two => slices(&self.boot::<4>()),
+        })
     }
 }
 
-impl Solution {
-    fn set(&mut self, x: i64, y: i64, z: i64, w: i64, c: char) {
-        if c == '#' {
-            self.data.insert((x, y, z, w), c);
+/// Where along a line of the initial slice its active cubes are, counting in characters.
+fn active(line: &str) -> Result<Vec<i64>, ParseError> {
+    let mut active = Vec::new();
+    for (x, c) in line.chars().enumerate() {
+        match c {
+            '#' => active.push(x as i64),
+            '.' => {}
+            _ => return Err(ParseError::new("expected '#' or '.'", &c.to_string(), x)),
         }
     }
+    Ok(active)
+}
 
-    fn analyse_part1(&mut self) -> Option<i64> {
-        let mut data = self.data.clone();
-        for _pass in 1..=6 {
-            data = Self::pass_part1(data);
-        }
-        Some(data.len() as i64)
+impl Solution {
+    fn analyse_part1(&self) -> Option<i64> {
+        Some(self.boot::<3>().count(true) as i64)
     }
 
     fn analyse_part2(&self) -> Option<i64> {
-        let mut data = self.data.clone();
-        for _pass in 1..=6 {
-            data = Self::pass_part2(data);
-        }
-        Some(data.len() as i64)
+        Some(self.boot::<4>().count(true) as i64)
     }
 
-    fn pass_part1(
-        data: HashMap<(i64, i64, i64, i64), char>,
-    ) -> HashMap<(i64, i64, i64, i64), char> {
-        let mut count = HashMap::new();
-        for ((x, y, z, w), c) in &data {
-            if *c != '#' {
-                continue;
-            }
-            for dz in -1..=1_i64 {
-                for dy in -1..=1_i64 {
-                    for dx in -1..=1_i64 {
-                        if dx.abs() + dy.abs() + dz.abs() != 0 {
-                            *count.entry((x + dx, y + dy, z + dz, *w)).or_insert(0) += 1;
-                        }
-                    }
-                }
-            }
-        }
-        let mut next_data = HashMap::new();
-        for ((x, y, z, w), c) in count {
-            let was_active = matches!(data.get(&(x, y, z, w)), Some('#'));
-            if c == 3 || (was_active && c == 2) {
-                next_data.insert((x, y, z, w), '#');
-            }
-        }
-        next_data
+    /// Run the six-cycle boot process in `N` dimensions, returning the active cubes.
+    fn boot<const N: usize>(&self) -> World<N, bool> {
+        let world: World<N, bool> = self
+            .active
+            .iter()
+            .map(|&(x, y)| {
+                let mut point = [0; N];
+                point[0] = x;
+                point[1] = y;
+                (point, true)
+            })
+            .collect();
+        Automaton::new(ConwayCubes, world).run(6).clone()
     }
+}
 
-    fn pass_part2(
-        data: HashMap<(i64, i64, i64, i64), char>,
-    ) -> HashMap<(i64, i64, i64, i64), char> {
-        let mut count = HashMap::new();
-        for ((x, y, z, w), c) in &data {
-            if *c != '#' {
-                continue;
-            }
-            for dz in -1..=1_i64 {
-                for dy in -1..=1_i64 {
-                    for dx in -1..=1_i64 {
-                        for dw in -1..=1_i64 {
-                            if dx.abs() + dy.abs() + dz.abs() + dw.abs() != 0 {
-                                *count.entry((x + dx, y + dy, z + dz, w + dw)).or_insert(0) += 1;
-                            }
-                        }
-                    }
-                }
-            }
+/// Each x-y slice holding an active cube, drawn across the bounds of every active cube, under
+/// a heading giving its position in the remaining dimensions, as in the puzzle.
+fn slices<const N: usize>(world: &World<N, bool>) -> String {
+    let mut slices: BTreeMap<&[i64], HashSet<(i64, i64)>> = BTreeMap::new();
+    for (point, _) in world.iter() {
+        slices
+            .entry(&point[2..])
+            .or_default()
+            .insert((point[0], point[1]));
+    }
+    let bounds = |axis: usize| {
+        let values = || world.iter().map(move |(point, _)| point[axis]);
+        (values().min().unwrap_or(0), values().max().unwrap_or(0))
+    };
+    let ((left, right), (top, bottom)) = (bounds(0), bounds(1));
+    let mut picture = String::new();
+    for (position, active) in &slices {
+        if !picture.is_empty() {
+            picture.push('\n');
         }
-        let mut next_data = HashMap::new();
-        for ((x, y, z, w), c) in count {
-            let was_active = matches!(data.get(&(x, y, z, w)), Some('#'));
-            if c == 3 || (was_active && c == 2) {
-                next_data.insert((x, y, z, w), '#');
+        let heading: Vec<String> = ["z", "w"]
+            .iter()
+            .zip(position.iter())
+            .map(|(axis, value)| format!("{}={}", axis, value))
+            .collect();
+        picture.push_str(&heading.join(", "));
+        picture.push('\n');
+        for y in top..=bottom {
+            for x in left..=right {
+                picture.push(if active.contains(&(x, y)) { '#' } else { '.' });
             }
+            picture.push('\n');
         }
-        next_data
+    }
+    picture
+}
+
+/// A cube becomes active with exactly 3 active neighbours, and stays active with 2 or 3.
+#[derive(Debug)]
+struct ConwayCubes;
+
+impl<const N: usize> Rules<N> for ConwayCubes {
+    type State = bool;
+
+    fn neighbourhood(&self, point: &Point<N>) -> Cow<'_, [Point<N>]> {
+        Cow::Owned(moore(point))
+    }
+
+    fn transition(&self, active: bool, neighbours: &[bool]) -> bool {
+        let count = neighbours.iter().filter(|&&n| n).count();
+        count == 3 || (active && count == 2)
     }
 }
@@ -0,0 +1,22 @@
+use day17::Solution;
+use puzzle::{Answer, Puzzle};
+
+#[test]
+fn example() {
+    let mut solution =
+        Solution::parse(include_str!("../../inputs/2020/input.day17.small")).unwrap();
+    solution.analyse();
+    assert_eq!(solution.answer_part1(), Answer::Integer(112));
+    assert_eq!(solution.answer_part2(), Answer::Integer(848));
+}
+
+#[test]
+fn check() {
+    // Columns count characters, whether checking or parsing
+    let input = ".é#\n#.x\n";
+    let problems = Solution::check(input);
+    let columns: Vec<_> = problems.iter().map(|e| (e.line, e.column)).collect();
+    assert_eq!(columns, vec![(Some(1), 2), (Some(2), 3)]);
+    let e = Solution::parse(input).unwrap_err();
+    assert_eq!(e.to_string(), "1:2: expected '#' or '.': 'é'");
+}
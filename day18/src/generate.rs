@@ -0,0 +1,35 @@
+use rand::Rng;
+use std::io::Write;
+
+/// `size` expressions of single digits, with at most twelve digits in each so that their values
+/// stay well within an `i64`.
+pub fn input(size: usize, rng: &mut impl Rng, out: &mut dyn Write) -> anyhow::Result<()> {
+    for _ in 0..size {
+        let digits = rng.gen_range(2..=12);
+        writeln!(out, "{}", expression(digits, 0, rng))?;
+    }
+    Ok(())
+}
+
+/// An expression of exactly `digits` digits, with parentheses nested up to three deep.
+fn expression(digits: usize, depth: usize, rng: &mut impl Rng) -> String {
+    let terms = match depth < 3 {
+        true => rng.gen_range(2..=digits.min(4)),
+        false => digits,
+    };
+    let mut sizes = vec![1; terms];
+    for _ in terms..digits {
+        sizes[rng.gen_range(0..terms)] += 1;
+    }
+    let mut text = String::new();
+    for (idx, size) in sizes.into_iter().enumerate() {
+        if idx > 0 {
+            text.push_str(if rng.gen_bool(0.5) { " + " } else { " * " });
+        }
+        match size {
+            1 => text.push(char::from_digit(rng.gen_range(1..10), 10).unwrap()),
+            size => text.push_str(&format!("({})", expression(size, depth + 1, rng))),
+        }
+    }
+    text
+}
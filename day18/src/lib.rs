@@ -1,21 +1,11 @@
 use itertools::Itertools;
 use log::debug;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
-use std::num::ParseIntError;
+use puzzle::{check_lines, Answer, ParseError, Puzzle};
+use rand::rngs::StdRng;
+use std::io::{BufRead, Write};
 use std::str::FromStr;
 
-pub fn load(filename: &str) -> anyhow::Result<Solution> {
-    let file = File::open(filename)?;
-
-    let reader = BufReader::new(file);
-    let mut solution = Solution::default();
-    for line in reader.lines() {
-        let line = line?;
-        solution.add(Sum::from_str(&line)?);
-    }
-    Ok(solution)
-}
+mod generate;
 
 #[derive(Debug, Default)]
 pub struct Solution {
@@ -24,21 +14,42 @@ pub struct Solution {
     answer_part2: Option<i64>,
 }
 
-impl Solution {
-    pub fn analyse(&mut self) {
-        let total_part1 = self.sums.iter().map(|sum| sum.evaluate_part1()).sum();
-        let total_part2 = self.sums.iter().map(|sum| sum.evaluate_part2()).sum();
+impl Puzzle for Solution {
+    const YEAR: u32 = 2020;
+    const DAY: u32 = 18;
+    const TITLE: &'static str = "Operation Order";
+    const VERSION: &'static str = env!("CARGO_PKG_VERSION");
+
+    fn from_reader(reader: impl BufRead) -> anyhow::Result<Self> {
+        let mut solution = Solution::default();
+        for (idx, line) in reader.lines().enumerate() {
+            let line = line?;
+            let sum = Sum::from_str(&line).map_err(|e| e.at_line(idx + 1))?;
+            solution.add(sum);
+        }
+        Ok(solution)
+    }
 
-        self.answer_part1 = Some(total_part1);
-        self.answer_part2 = Some(total_part2);
+    fn check(input: &str) -> Vec<ParseError> {
+        check_lines(input, |line| Sum::from_str(line).map(drop))
     }
 
-    pub fn answer_part1(&self) -> Option<i64> {
-        self.answer_part1
+    fn generate(size: usize, rng: &mut StdRng, out: &mut dyn Write) -> anyhow::Result<()> {
+        generate::input(size, rng, out)
     }
 
-    pub fn answer_part2(&self) -> Option<i64> {
-        self.answer_part2
+    fn analyse(&mut self) {
+        // A sum which does not evaluate, or overflows, leaves its part unanswered
+        self.answer_part1 = self.total(Sum::evaluate_part1);
+        self.answer_part2 = self.total(Sum::evaluate_part2);
+    }
+
+    fn answer_part1(&self) -> Answer {
+        self.answer_part1.into()
+    }
+
+    fn answer_part2(&self) -> Answer {
+        self.answer_part2.into()
     }
 }
 
@@ -46,6 +57,12 @@ impl Solution {
     fn add(&mut self, sum: Sum) {
         self.sums.push(sum);
     }
+
+    fn total(&self, evaluate: impl Fn(&Sum) -> Option<i64>) -> Option<i64> {
+        self.sums
+            .iter()
+            .try_fold(0_i64, |total, sum| total.checked_add(evaluate(sum)?))
+    }
 }
 
 #[derive(Debug, Default)]
@@ -72,7 +89,7 @@ impl CalculationPart1 {
 
     fn value(&self) -> Option<i64> {
         match self.calc.len() {
-            1 => self.calc.get(0).and_then(|v| match v {
+            1 => self.calc.first().and_then(|v| match v {
                 AlgorithmicObject::Value(v) => Some(*v),
                 _ => None,
             }),
@@ -80,20 +97,22 @@ impl CalculationPart1 {
         }
     }
 
-    fn push(&mut self, value: AlgorithmicObject) {
+    /// Push the next value or operator, or `None` if it is out of place or overflows.
+    fn push(&mut self, value: AlgorithmicObject) -> Option<()> {
         use AlgorithmicObject::*;
 
         self.calc.push(value);
         if self.calc.len() == 3 {
             let triple = self.calc.iter().collect_tuple();
             let r = match triple {
-                Some((Value(lhs), Add, Value(rhs))) => Value(lhs + rhs),
-                Some((Value(lhs), Mul, Value(rhs))) => Value(lhs * rhs),
-                _ => unreachable!(),
+                Some((Value(lhs), Add, Value(rhs))) => Value(lhs.checked_add(*rhs)?),
+                Some((Value(lhs), Mul, Value(rhs))) => Value(lhs.checked_mul(*rhs)?),
+                _ => return None,
             };
             self.calc.clear();
             self.calc.push(r);
         }
+        Some(())
     }
 }
 
@@ -118,7 +137,7 @@ impl CalculationPart2 {
             match (prev2, prev, v) {
                 (Some(Value(lhs)), Some(Add), Value(rhs)) => {
                     prev2 = None;
-                    prev = Some(Value(lhs + rhs));
+                    prev = Some(Value(lhs.checked_add(*rhs)?));
                 }
                 _ => {
                     if let Some(v) = prev2 {
@@ -145,7 +164,7 @@ impl CalculationPart2 {
             match (prev2, prev, v) {
                 (Some(Value(lhs)), Some(Mul), Value(rhs)) => {
                     prev2 = None;
-                    prev = Some(Value(lhs * rhs));
+                    prev = Some(Value(lhs.checked_mul(*rhs)?));
                 }
                 _ => {
                     if let Some(v) = prev2 {
@@ -165,7 +184,7 @@ impl CalculationPart2 {
         debug!("next: {:?}", next);
         let calc = next;
         match calc.len() {
-            1 => calc.get(0).and_then(|v| match v {
+            1 => calc.first().and_then(|v| match v {
                 AlgorithmicObject::Value(v) => Some(*v),
                 _ => None,
             }),
@@ -173,13 +192,16 @@ impl CalculationPart2 {
         }
     }
 
-    fn push(&mut self, value: AlgorithmicObject) {
+    fn push(&mut self, value: AlgorithmicObject) -> Option<()> {
         self.calc.push(value);
+        Some(())
     }
 }
 
 impl Sum {
-    fn evaluate_part1_part(s: &[u8]) -> (i64, usize) {
+    /// The value of the sum up to the end of the enclosing group, and where that is, or `None`
+    /// if the sum is malformed or overflows.
+    fn evaluate_part1_part(s: &[u8]) -> Option<(i64, usize)> {
         use AlgorithmicObject::*;
 
         let mut cur_val = None;
@@ -187,29 +209,31 @@ impl Sum {
         let mut idx = 0;
         while idx < s.len() {
             let c = s[idx];
-            match c {
-                b' ' => {
-                    if let Some(v) = cur_val {
-                        stack.push(Value(v));
-                        cur_val = None;
-                    }
+            // A number ends at whatever follows it, with or without a space between
+            if !c.is_ascii_digit() {
+                if let Some(v) = cur_val.take() {
+                    stack.push(Value(v))?;
                 }
+            }
+            match c {
+                b' ' => {}
                 b'0'..=b'9' => {
+                    let digit = (c - b'0') as i64;
                     let next_val = match cur_val {
-                        None => (c as i8 - 0x30) as i64,
-                        Some(v) => v + (c as i8 - 0x30) as i64,
+                        None => digit,
+                        Some(v) => v.checked_mul(10)?.checked_add(digit)?,
                     };
                     cur_val = Some(next_val);
                 }
                 b'+' => {
-                    stack.push(Add);
+                    stack.push(Add)?;
                 }
                 b'*' => {
-                    stack.push(Mul);
+                    stack.push(Mul)?;
                 }
                 b'(' => {
                     debug!("inner");
-                    let (result, mut shift) = Self::evaluate_part1_part(&s[idx + 1..]);
+                    let (result, mut shift) = Self::evaluate_part1_part(&s[idx + 1..])?;
                     shift += 1;
                     debug!(
                         "inner shift idx from {} to {} by {} (next = {:?})",
@@ -219,24 +243,21 @@ impl Sum {
                         s.get(idx + shift)
                     );
                     idx += shift;
-                    stack.push(Value(result));
+                    stack.push(Value(result))?;
                 }
                 b')' => {
-                    if let Some(v) = cur_val {
-                        stack.push(Value(v));
-                    }
                     debug!("return from inner ({:?})", stack);
-                    return (stack.value().unwrap(), idx);
+                    return Some((stack.value()?, idx));
                 }
-                _ => unreachable!(),
+                _ => return None,
             };
             debug!("stack:= {:?}", stack);
             idx += 1;
         }
-        (stack.value().unwrap(), idx)
+        Some((stack.value()?, idx))
     }
 
-    fn evaluate_part2_part(s: &[u8]) -> (i64, usize) {
+    fn evaluate_part2_part(s: &[u8]) -> Option<(i64, usize)> {
         use AlgorithmicObject::*;
 
         let mut cur_val = None;
@@ -244,29 +265,31 @@ impl Sum {
         let mut idx = 0;
         while idx < s.len() {
             let c = s[idx];
-            match c {
-                b' ' => {
-                    if let Some(v) = cur_val {
-                        stack.push(Value(v));
-                        cur_val = None;
-                    }
+            // A number ends at whatever follows it, with or without a space between
+            if !c.is_ascii_digit() {
+                if let Some(v) = cur_val.take() {
+                    stack.push(Value(v))?;
                 }
+            }
+            match c {
+                b' ' => {}
                 b'0'..=b'9' => {
+                    let digit = (c - b'0') as i64;
                     let next_val = match cur_val {
-                        None => (c as i8 - 0x30) as i64,
-                        Some(v) => v + (c as i8 - 0x30) as i64,
+                        None => digit,
+                        Some(v) => v.checked_mul(10)?.checked_add(digit)?,
                     };
                     cur_val = Some(next_val);
                 }
                 b'+' => {
-                    stack.push(Add);
+                    stack.push(Add)?;
                 }
                 b'*' => {
-                    stack.push(Mul);
+                    stack.push(Mul)?;
                 }
                 b'(' => {
                     debug!("inner");
-                    let (result, mut shift) = Self::evaluate_part2_part(&s[idx + 1..]);
+                    let (result, mut shift) = Self::evaluate_part2_part(&s[idx + 1..])?;
                     shift += 1;
                     debug!(
                         "inner shift idx from {} to {} by {} (next = {:?})",
@@ -276,44 +299,92 @@ impl Sum {
                         s.get(idx + shift)
                     );
                     idx += shift;
-                    stack.push(Value(result));
+                    stack.push(Value(result))?;
                 }
                 b')' => {
-                    if let Some(v) = cur_val {
-                        stack.push(Value(v));
-                    }
                     debug!("return from inner ({:?})", stack);
-                    return (stack.value().unwrap(), idx);
+                    return Some((stack.value()?, idx));
                 }
-                _ => unreachable!(),
+                _ => return None,
             };
             debug!("stack:= {:?}", stack);
             idx += 1;
         }
-        (stack.value().unwrap(), idx)
+        Some((stack.value()?, idx))
     }
 
-    fn evaluate_part1(&self) -> i64 {
+    fn evaluate_part1(&self) -> Option<i64> {
         let s = self.s.clone() + " ";
         let s = s.as_bytes();
-        let (r, _idx) = Self::evaluate_part1_part(s);
+        let (r, _idx) = Self::evaluate_part1_part(s)?;
         debug!("{} => {:?}", self.s.len(), r);
-        r
+        Some(r)
     }
 
-    fn evaluate_part2(&self) -> i64 {
+    fn evaluate_part2(&self) -> Option<i64> {
         let s = self.s.clone() + " ";
         let s = s.as_bytes();
-        let (r, _idx) = Self::evaluate_part2_part(s);
+        let (r, _idx) = Self::evaluate_part2_part(s)?;
         debug!("{} => {:?}", self.s.len(), r);
-        r
+        Some(r)
     }
 }
 
+/// Whether a sum expects a number or group next, or an operator or the end of a group.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Expect {
+    Operand,
+    Operator,
+}
+
 impl FromStr for Sum {
-    type Err = ParseIntError;
+    type Err = ParseError;
 
+    /// Operands (numbers or parenthesised sums) alternate with `+` and `*`, starting and ending
+    /// with an operand.
     fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut depth = 0;
+        let mut expect = Expect::Operand;
+        let mut chars = s.char_indices().peekable();
+        while let Some((idx, c)) = chars.next() {
+            let text = c.to_string();
+            expect = match (c, expect) {
+                (' ', expect) => expect,
+                ('0'..='9', Expect::Operand) => {
+                    while chars.next_if(|(_, c)| c.is_ascii_digit()).is_some() {}
+                    Expect::Operator
+                }
+                ('(', Expect::Operand) => {
+                    depth += 1;
+                    Expect::Operand
+                }
+                ('+' | '*', Expect::Operator) => Expect::Operand,
+                (')', Expect::Operator) if depth > 0 => {
+                    depth -= 1;
+                    Expect::Operator
+                }
+                (')', Expect::Operator) => return Err(ParseError::new("unmatched ')'", ")", idx)),
+                ('+' | '*' | ')', Expect::Operand) => {
+                    return Err(ParseError::new("expected a number or '('", &text, idx))
+                }
+                ('0'..='9' | '(', Expect::Operator) => {
+                    return Err(ParseError::new("expected '+', '*' or ')'", &text, idx))
+                }
+                _ => {
+                    return Err(ParseError::new(
+                        "expected a digit, '+', '*', '(' or ')'",
+                        &text,
+                        idx,
+                    ))
+                }
+            };
+        }
+        if expect == Expect::Operand {
+            return Err(ParseError::new("expected a number or '('", s, s.len()));
+        }
+        if depth > 0 {
+            return Err(ParseError::new("unclosed '('", s, 0));
+        }
         Ok(Self { s: s.to_string() })
     }
 }
@@ -325,7 +396,50 @@ mod tests {
 
     fn test_part2(sum: &str, expected: i64) {
         let sum = Sum::from_str(sum).unwrap();
-        assert_eq!(sum.evaluate_part2(), expected);
+        assert_eq!(sum.evaluate_part2(), Some(expected));
+    }
+
+    #[test]
+    fn parse_unmatched() {
+        let result = Sum::from_str("1 + 2)").unwrap_err();
+        assert_eq!(result.column, 6);
+        assert!(Sum::from_str("(1 + 2").is_err());
+        assert!(Sum::from_str("1 - 2").is_err());
+    }
+
+    #[test]
+    fn parse_malformed() {
+        for (sum, column) in [("", 1), ("1 + + 2", 5), ("()", 2), ("1 2", 3), ("(1 +)", 5)] {
+            assert_eq!(Sum::from_str(sum).unwrap_err().column, column, "{:?}", sum);
+        }
+    }
+
+    #[test]
+    fn multi_digit_operands() {
+        let sum = Sum::from_str("12 + 1 * (10 + 205)").unwrap();
+        assert_eq!(sum.evaluate_part1(), Some(2795));
+        assert_eq!(sum.evaluate_part2(), Some(2795));
+        test_part2("12 * 3 + 10", 156);
+    }
+
+    #[test]
+    fn optional_spaces() {
+        let sum = Sum::from_str("1+2*(3 +4)").unwrap();
+        assert_eq!(sum.evaluate_part1(), Some(21));
+        assert_eq!(sum.evaluate_part2(), Some(21));
+        test_part2("2*3+(4*5)", 46);
+    }
+
+    #[test]
+    fn overflow_is_unsolved() {
+        let sum = Sum::from_str("99999999999 * 99999999999").unwrap();
+        assert_eq!(sum.evaluate_part1(), None);
+        assert_eq!(sum.evaluate_part2(), None);
+        let sum = Sum::from_str("99999999999999999999").unwrap();
+        assert_eq!(sum.evaluate_part1(), None);
+        let mut solution = Solution::parse("9223372036854775807\n1\n").unwrap();
+        solution.analyse();
+        assert!(!solution.answer_part1().is_solved());
     }
 
     #[test]
@@ -0,0 +1,11 @@
+use day18::Solution;
+use puzzle::{Answer, Puzzle};
+
+#[test]
+fn example() {
+    let mut solution =
+        Solution::parse(include_str!("../../inputs/2020/input.day18.small")).unwrap();
+    solution.analyse();
+    assert_eq!(solution.answer_part1(), Answer::Integer(26457));
+    assert_eq!(solution.answer_part2(), Answer::Integer(694173));
+}
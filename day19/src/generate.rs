@@ -0,0 +1,163 @@
+use crate::{Rule, RuleLogic};
+use rand::seq::SliceRandom;
+use rand::Rng;
+use std::collections::HashMap;
+use std::io::Write;
+
+/// The rule numbers the puzzle fixes: `0: 8 11`, `8: 42` and `11: 42 31`.
+const FIXED: [usize; 5] = [0, 8, 11, 31, 42];
+
+/// A random grammar in which rules 42 and 31 each match messages of eight characters, those of
+/// 42 starting with 'a' and of 31 with 'b', followed by `size` messages: some matched by rule 0,
+/// some only once rules 8 and 11 loop, and the rest matched by neither.
+pub fn input(size: usize, rng: &mut impl Rng, out: &mut dyn Write) -> anyhow::Result<()> {
+    let mut grammar = Grammar::new(rng);
+    let forty_two = grammar.logic(8, Some('a'), rng);
+    grammar.insert(42, forty_two);
+    let thirty_one = grammar.logic(8, Some('b'), rng);
+    grammar.insert(31, thirty_one);
+    grammar.insert(0, RuleLogic::Chain(vec![vec![8, 11]]));
+    grammar.insert(8, RuleLogic::Chain(vec![vec![42]]));
+    grammar.insert(11, RuleLogic::Chain(vec![vec![42, 31]]));
+
+    let mut rules: Vec<Rule> = grammar
+        .logic
+        .iter()
+        .map(|(&number, logic)| Rule {
+            number,
+            logic: logic.clone(),
+        })
+        .collect();
+    // Shuffle from a fixed order, as the map's order differs from run to run
+    rules.sort_by_key(|rule| rule.number);
+    rules.shuffle(rng);
+    for rule in rules {
+        writeln!(out, "{}", rule)?;
+    }
+    writeln!(out)?;
+
+    for _ in 0..size {
+        let (repeats, closes) = match rng.gen_range(0..3) {
+            // Matched by rule 0 as it stands
+            0 => (2, 1),
+            // Only matched once rules 8 and 11 loop
+            1 => {
+                let closes = rng.gen_range(1..=2);
+                (rng.gen_range(closes + 2..=5), closes)
+            }
+            // Never matched: rule 11 needs as many 42s as 31s, and rule 8 at least one more
+            _ => {
+                let repeats = rng.gen_range(1..=3);
+                (repeats, rng.gen_range(repeats..=4))
+            }
+        };
+        let mut message = String::new();
+        for _ in 0..repeats {
+            grammar.sample(42, rng, &mut message);
+        }
+        for _ in 0..closes {
+            grammar.sample(31, rng, &mut message);
+        }
+        writeln!(out, "{}", message)?;
+    }
+    Ok(())
+}
+
+/// Rules matching fixed length messages, numbered at random.
+///
+/// The alternatives of every rule differ in the letter at some position, so that a message can
+/// only ever be matched one way; otherwise the ways of matching each part of a message multiply
+/// into far more than the solver can check.
+struct Grammar {
+    logic: HashMap<usize, RuleLogic>,
+    /// The rules made so far, by the length of their messages and the letter those start with.
+    made: HashMap<(usize, Option<char>), Vec<usize>>,
+    numbers: Vec<usize>,
+}
+
+impl Grammar {
+    fn new(rng: &mut impl Rng) -> Self {
+        let mut numbers: Vec<usize> = (0..200).filter(|n| !FIXED.contains(n)).collect();
+        numbers.shuffle(rng);
+        let mut grammar = Self {
+            logic: HashMap::new(),
+            made: HashMap::new(),
+            numbers,
+        };
+        for c in ['a', 'b'] {
+            let number = grammar.add(RuleLogic::Simple(c));
+            grammar.made.insert((1, Some(c)), vec![number]);
+        }
+        grammar
+    }
+
+    fn add(&mut self, logic: RuleLogic) -> usize {
+        let number = self.numbers.pop().expect("enough rule numbers");
+        self.insert(number, logic);
+        number
+    }
+
+    fn insert(&mut self, number: usize, logic: RuleLogic) {
+        self.logic.insert(number, logic);
+    }
+
+    /// A rule matching messages of `length` characters, starting with `first` if given, often
+    /// one already made.
+    fn rule(&mut self, length: usize, first: Option<char>, rng: &mut impl Rng) -> usize {
+        if let Some(made) = self.made.get(&(length, first)) {
+            if first.is_some() && length == 1 || rng.gen_bool(0.3) {
+                return *made.choose(rng).unwrap();
+            }
+        }
+        let logic = self.logic(length, first, rng);
+        let number = self.add(logic);
+        self.made.entry((length, first)).or_default().push(number);
+        number
+    }
+
+    /// Two alternatives matching messages of `length` characters, starting with `first` if
+    /// given, which differ in their first letter or, if that is fixed, in the letter halfway.
+    fn logic(&mut self, length: usize, first: Option<char>, rng: &mut impl Rng) -> RuleLogic {
+        let chains = match (length, first) {
+            (1, _) => vec![
+                vec![self.rule(1, Some('a'), rng)],
+                vec![self.rule(1, Some('b'), rng)],
+            ],
+            (_, None) => {
+                let half = length / 2;
+                ['a', 'b']
+                    .map(|c| {
+                        vec![
+                            self.rule(half, Some(c), rng),
+                            self.rule(length - half, None, rng),
+                        ]
+                    })
+                    .into()
+            }
+            (_, Some(first)) => {
+                let half = length / 2;
+                ['a', 'b']
+                    .map(|c| {
+                        vec![
+                            self.rule(half, Some(first), rng),
+                            self.rule(length - half, Some(c), rng),
+                        ]
+                    })
+                    .into()
+            }
+        };
+        RuleLogic::Chain(chains)
+    }
+
+    /// Append a random message matched by the numbered rule.
+    fn sample(&self, number: usize, rng: &mut impl Rng, message: &mut String) {
+        match &self.logic[&number] {
+            RuleLogic::Simple(c) => message.push(*c),
+            RuleLogic::Chain(chains) => {
+                for &number in chains.choose(rng).unwrap() {
+                    self.sample(number, rng, message);
+                }
+            }
+        }
+    }
+}
@@ -1,7 +1,9 @@
-use std::collections::HashMap;
-use std::io::BufRead;
-use std::io::BufReader;
-use std::fs::File;
+use puzzle::{Answer, Explanation, ParseError, Part, Puzzle};
+use rand::rngs::StdRng;
+use std::collections::{HashMap, HashSet};
+use std::fmt::Display;
+use std::io::{BufRead, Write};
+use tracing::{debug_span, instrument, Span};
 
 use nom::branch::alt;
 use nom::bytes::complete::tag;
@@ -15,22 +17,7 @@ use nom::sequence::terminated;
 use nom::IResult;
 use nom::Parser;
 
-pub fn load(filename: &str) -> anyhow::Result<Solution> {
-    let file = File::open(filename)?;
-
-    let reader = BufReader::new(file);
-    let mut solution = Solution::default();
-    for line in reader.lines().map_while(Result::ok) {
-        if line.trim().is_empty() {
-            continue;
-        }
-        match line.split_once(':') {
-            Some(_) => solution.add_rule(line),
-            None => solution.add_message(line),
-        }
-    }
-    Ok(solution)
-}
+mod generate;
 
 #[derive(Debug, Default)]
 pub struct Solution {
@@ -46,62 +33,198 @@ impl Solution {
     pub fn add_message(&mut self, message: String) {
         self.messages.push(message);
     }
+}
+
+impl Puzzle for Solution {
+    const YEAR: u32 = 2020;
+    const DAY: u32 = 19;
+    const TITLE: &'static str = "Monster Messages";
+    const VERSION: &'static str = env!("CARGO_PKG_VERSION");
+
+    fn from_reader(reader: impl BufRead) -> anyhow::Result<Self> {
+        let mut solution = Solution::default();
+        let mut numbers = Vec::new();
+        for (idx, line) in reader.lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            match line.split_once(':') {
+                Some(_) => {
+                    let rule = parse_rule(&line).map_err(|e| e.at_line(idx + 1))?;
+                    numbers.push((idx + 1, rule.number));
+                    solution.add_rule(line)
+                }
+                None => solution.add_message(line),
+            }
+        }
+        let rules = numbers
+            .iter()
+            .zip(&solution.rules)
+            .map(|(&(line_number, number), line)| (line_number, line.as_str(), number));
+        if let Some(e) = undefined(&rules.collect::<Vec<_>>()).into_iter().next() {
+            return Err(e.into());
+        }
+        Ok(solution)
+    }
+
+    /// Beyond each rule parsing, every rule referred to must be defined: rule 0, which messages
+    /// must match, those referred to by other rules, and rules 42 and 31, which part 2 refers to.
+    fn check(input: &str) -> Vec<ParseError> {
+        let mut problems = Vec::new();
+        let mut rules = Vec::new();
+        for (idx, line) in input.lines().enumerate() {
+            if line.split_once(':').is_some() {
+                match parse_rule(line) {
+                    Ok(rule) => rules.push((idx + 1, line, rule.number)),
+                    Err(e) => problems.push(e.at_line(idx + 1)),
+                }
+            }
+        }
+        problems.extend(undefined(&rules));
+        let defined: HashSet<usize> = rules.iter().map(|(_, _, number)| *number).collect();
+        for number in [42, 31] {
+            if !defined.contains(&number) {
+                problems.push(ParseError::new(
+                    format!("expected a rule {}, which part 2 refers to", number),
+                    "",
+                    0,
+                ));
+            }
+        }
+        problems
+    }
 
-    pub fn analyse(&mut self) {
+    fn generate(size: usize, rng: &mut StdRng, out: &mut dyn Write) -> anyhow::Result<()> {
+        generate::input(size, rng, out)
+    }
 
+    fn analyse(&mut self) {}
+
+    fn answer_part1(&self) -> Answer {
+        let rules = match self.part_rules(Part::One) {
+            Ok(rules) => rules,
+            Err(e) => return Answer::Unsolved(e.to_string()),
+        };
+        let answer = self
+            .messages
+            .iter()
+            .filter(|message| check_message(message.to_string(), &rules))
+            .count();
+        Answer::Integer(answer as i64)
     }
 
-    pub fn answer_part1(&self) -> Option<i64> {
-        let rules = rules(self.rules.iter().cloned());
-        let answer = self.messages.iter().filter(|message| check_message(message.to_string(), &rules)).count();
-        Some(answer as i64)
+    fn answer_part2(&self) -> Answer {
+        let rules = match self.part_rules(Part::Two) {
+            Ok(rules) => rules,
+            Err(e) => return Answer::Unsolved(e.to_string()),
+        };
+        let answer = self
+            .messages
+            .iter()
+            .filter(|message| check_message(message.to_string(), &rules))
+            .count();
+        Answer::Integer(answer as i64)
     }
 
-    pub fn answer_part2(&self) -> Option<i64> {
-        let mut part2_rules = self.rules.clone();
-        part2_rules.push("8: 42 | 42 8".to_string());
-        part2_rules.push("11: 42 31 | 42 11 31".to_string());
-        let rules = rules(part2_rules.iter().cloned());
-        let answer = self.messages.iter().filter(|message| check_message(message.to_string(), &rules)).count();
-        Some(answer as i64)
+    fn explain(&self, part: Part) -> Option<Explanation> {
+        let rules = self.part_rules(part).ok()?;
+        let (matched, unmatched): (Vec<&String>, Vec<&String>) = self
+            .messages
+            .iter()
+            .partition(|message| check_message(message.to_string(), &rules));
+        let mut facts = vec![
+            ("matched", matched.len().into()),
+            ("unmatched", unmatched.len().into()),
+        ];
+        // The rule tree of the first message to match, as an example
+        let example = matched.first().and_then(|message| {
+            derive(&rules, 0, message)
+                .into_iter()
+                .find(|(_tree, rest)| rest.is_empty())
+        });
+        if let Some((tree, _rest)) = example {
+            facts.push(("example", tree));
+        }
+        Some(Explanation::record(facts))
+    }
+}
+
+impl Solution {
+    /// The rules for a part: part 2 replaces rules 8 and 11 with looping ones.
+    fn part_rules(&self, part: Part) -> Result<HashMap<usize, RuleLogic>, ParseError> {
+        let mut part_rules = self.rules.clone();
+        if part == Part::Two {
+            part_rules.push("8: 42 | 42 8".to_string());
+            part_rules.push("11: 42 31 | 42 11 31".to_string());
+        }
+        let rules = rules(part_rules.into_iter())?;
+        // Part 2's rules refer to rules 42 and 31, which the input may not define
+        let missing = rules.values().flat_map(|logic| match logic {
+            RuleLogic::Simple(_) => Vec::new(),
+            RuleLogic::Chain(chains) => chains.concat(),
+        });
+        match missing
+            .chain([0])
+            .filter(|number| !rules.contains_key(number))
+            .min()
+        {
+            Some(number) => Err(ParseError::new(
+                format!("rule {} is not defined", number),
+                "",
+                0,
+            )),
+            None => Ok(rules),
+        }
+    }
+}
+
+/// Every reference to a rule which is not defined, given each rule's line number, line and
+/// number, along with rule 0 if that is not defined, as messages must match it.
+fn undefined(rules: &[(usize, &str, usize)]) -> Vec<ParseError> {
+    let mut problems = Vec::new();
+    let defined: HashSet<usize> = rules.iter().map(|(_, _, number)| *number).collect();
+    for (line_number, line, _) in rules {
+        let mut offset = line.find(": ").map_or(line.len(), |colon| colon + 2);
+        for token in line[offset..].split(' ') {
+            match token.parse::<usize>() {
+                Ok(number) if !defined.contains(&number) => {
+                    let e =
+                        ParseError::new(format!("rule {} is not defined", number), token, offset);
+                    problems.push(e.at_line(*line_number));
+                }
+                _ => {}
+            }
+            offset += token.len() + 1;
+        }
+    }
+    if !defined.contains(&0) {
+        problems.push(ParseError::new(
+            "expected a rule 0, which messages must match",
+            "",
+            0,
+        ));
     }
+    problems
 }
 
 /// Check an input line of text against the rule collection
 pub fn check_message(message: String, rules: &HashMap<usize, RuleLogic>) -> bool {
-    let solutions = process_rule(rules, 0, message, 0);
+    let _span = debug_span!("message", %message).entered();
+    let solutions = process_rule(rules, 0, message);
     // If any solution exists that has consumed the whole input, this is a pass
     solutions.contains(&String::from(""))
 }
 
-fn process_rule(
-    rules: &HashMap<usize, RuleLogic>,
-    index: usize,
-    input: String,
-    indent: usize,
-) -> Vec<String> {
-    let rule = &rules[&index];
-    log::debug!(
-        "{:-indent$}Checking: {:2}: {:?} Input: {}",
-        "",
-        index,
-        rule,
-        input,
-        indent = indent,
-    );
-    let result: Vec<String> = match rule {
+#[instrument(level = "trace", skip(rules), fields(matched))]
+fn process_rule(rules: &HashMap<usize, RuleLogic>, index: usize, input: String) -> Vec<String> {
+    let result: Vec<String> = match &rules[&index] {
         // Match a single char
         RuleLogic::Simple(c) => simple_rule(*c, input).into_iter().collect(),
         // Or match a chain
-        RuleLogic::Chain(indexes) => handle_chains(indexes, rules, input, indent),
+        RuleLogic::Chain(indexes) => handle_chains(indexes, rules, input),
     };
-    log::debug!(
-        "{:-indent$}{} {:?}",
-        "",
-        !result.is_empty(),
-        result,
-        indent = indent,
-    );
+    Span::current().record("matched", !result.is_empty());
     // Return whatever's left over from the input
     result
 }
@@ -117,7 +240,7 @@ pub fn simple_rule(rule_char: char, input: String) -> Option<String> {
         // Only take this first one if it matches the character in our rule
         .filter(|c2| *c2 == rule_char)
         // If it matches, return the rest of the input
-        .map(|_| String::from(&input[1..]))
+        .map(|c| String::from(&input[c.len_utf8()..]))
 }
 
 // Takes a bunch of alternate rule index chains, We must try each possibility
@@ -125,26 +248,19 @@ fn handle_chains(
     chains: &[Vec<usize>],
     rules: &HashMap<usize, RuleLogic>,
     input: String,
-    indent: usize,
 ) -> Vec<String> {
     // Collect all the possibilites, and use the one that consumes the most
     chains
         // Look at every posssible chain
         .iter()
         // Only take chains that pass
-        .flat_map(|this_chain| chain(this_chain, rules, input.clone(), indent + 1))
+        .flat_map(|this_chain| chain(this_chain, rules, input.clone()))
         .collect()
 }
 
-
 /// Takes a chain of rule indexes, if they all match, it returns the rest of the string
 /// If any fail, it returns an empty vec
-fn chain(
-    chain: &[usize],
-    rules: &HashMap<usize, RuleLogic>,
-    input: String,
-    indent: usize,
-) -> Vec<String> {
+fn chain(chain: &[usize], rules: &HashMap<usize, RuleLogic>, input: String) -> Vec<String> {
     chain
         .iter()
         // Try to go through all the links in the chain
@@ -157,7 +273,7 @@ fn chain(
                 // then flatten them into the possible output solutions (which
                 // will be used for input to the next link in the chain, or for
                 // the last link, returned)
-                .flat_map(|input| process_rule(rules, *index, input.clone(), indent))
+                .flat_map(|input| process_rule(rules, *index, input.clone()))
                 .collect();
             if new_solutions.is_empty() {
                 // If the next link, using the previous output as input, found no solutions
@@ -172,6 +288,57 @@ fn chain(
         .unwrap_or_else(Vec::new)
 }
 
+/// Every way a rule matches the start of the input, as the tree of rules it matched along
+/// with the rest of the input
+fn derive<'a>(
+    rules: &HashMap<usize, RuleLogic>,
+    index: usize,
+    input: &'a str,
+) -> Vec<(Explanation, &'a str)> {
+    let matches: Vec<(Vec<Explanation>, &str)> = match &rules[&index] {
+        RuleLogic::Simple(c) => input
+            .strip_prefix(*c)
+            .map(|rest| (Vec::new(), rest))
+            .into_iter()
+            .collect(),
+        RuleLogic::Chain(chains) => chains
+            .iter()
+            .flat_map(|chain| {
+                // Extend every partial match by each way the next link matches its rest
+                chain
+                    .iter()
+                    .fold(vec![(Vec::new(), input)], |partials, index| {
+                        partials
+                            .into_iter()
+                            .flat_map(|(parts, rest)| {
+                                derive(rules, *index, rest)
+                                    .into_iter()
+                                    .map(move |(part, rest)| {
+                                        let mut parts = parts.clone();
+                                        parts.push(part);
+                                        (parts, rest)
+                                    })
+                            })
+                            .collect()
+                    })
+            })
+            .collect(),
+    };
+    matches
+        .into_iter()
+        .map(|(parts, rest)| {
+            let mut facts = vec![
+                ("rule", index.into()),
+                ("text", input[..input.len() - rest.len()].into()),
+            ];
+            if !parts.is_empty() {
+                facts.push(("parts", Explanation::List(parts)));
+            }
+            (Explanation::record(facts), rest)
+        })
+        .collect()
+}
+
 #[derive(Debug, PartialEq)]
 pub struct Rule {
     pub number: usize,
@@ -216,12 +383,74 @@ pub fn rule(input: &str) -> IResult<&str, Rule> {
     Ok((rest, Rule { number, logic }))
 }
 
+impl Display for Rule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.number, self.logic)
+    }
+}
+
+impl Display for RuleLogic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RuleLogic::Simple(c) => write!(f, "\"{}\"", c),
+            RuleLogic::Chain(chains) => {
+                for (idx, chain) in chains.iter().enumerate() {
+                    if idx > 0 {
+                        write!(f, " | ")?;
+                    }
+                    let chain = chain.iter().map(|rule| rule.to_string());
+                    write!(f, "{}", chain.collect::<Vec<_>>().join(" "))?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Parses a whole line as a single rule
+pub fn parse_rule(line: &str) -> Result<Rule, ParseError> {
+    let offset = |rest: &str| line.len() - rest.len();
+    match rule(line) {
+        Ok(("", rule)) => Ok(rule),
+        Ok((rest, _rule)) => Err(ParseError::new(
+            "unexpected trailing input",
+            rest,
+            offset(rest),
+        )),
+        Err(nom::Err::Error(e) | nom::Err::Failure(e)) => Err(ParseError::new(
+            "expected '<n>: \"<char>\"' or '<n>: <rules> | <rules>'",
+            e.input,
+            offset(e.input),
+        )),
+        Err(nom::Err::Incomplete(_)) => Err(ParseError::new("incomplete rule", line, 0)),
+    }
+}
+
 /// Parses a bunch of rules and returns their logic in order
-pub fn rules(
-    lines: impl Iterator<Item = String>,
-) -> HashMap<usize, RuleLogic> {
+pub fn rules(lines: impl Iterator<Item = String>) -> Result<HashMap<usize, RuleLogic>, ParseError> {
     lines
-        .map(|line| rule(line.as_str()).map(|(_rest, rule)| rule).unwrap())
-        .map(|rule| (rule.number, rule.logic))
+        .map(|line| parse_rule(&line).map(|rule| (rule.number, rule.logic)))
         .collect()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn rule() -> impl Strategy<Value = Rule> {
+        let chain = prop::collection::vec(any::<usize>(), 1..4);
+        let logic = prop_oneof![
+            any::<char>().prop_map(RuleLogic::Simple),
+            prop::collection::vec(chain, 1..4).prop_map(RuleLogic::Chain),
+        ];
+        (any::<usize>(), logic).prop_map(|(number, logic)| Rule { number, logic })
+    }
+
+    proptest! {
+        #[test]
+        fn display_round_trip(rule in rule()) {
+            prop_assert_eq!(parse_rule(&rule.to_string()).unwrap(), rule);
+        }
+    }
+}
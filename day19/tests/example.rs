@@ -0,0 +1,42 @@
+use day19::Solution;
+use puzzle::{Answer, Explanation, Part, Puzzle};
+
+#[test]
+fn example() {
+    let mut solution =
+        Solution::parse(include_str!("../../inputs/2020/input.day19.small")).unwrap();
+    solution.analyse();
+    assert_eq!(solution.answer_part1(), Answer::Integer(3));
+    assert_eq!(solution.answer_part2(), Answer::Integer(12));
+}
+
+#[test]
+fn explain() {
+    let mut solution =
+        Solution::parse(include_str!("../../inputs/2020/input.day19.small")).unwrap();
+    solution.analyse();
+    let Some(Explanation::Record(facts)) = solution.explain(Part::Two) else {
+        panic!("expected a record");
+    };
+    assert_eq!(facts[0], ("matched".to_string(), 12_i64.into()));
+    assert_eq!(facts[1], ("unmatched".to_string(), 3_i64.into()));
+    // The example tree covers its whole message
+    let Explanation::Record(example) = &facts[2].1 else {
+        panic!("expected an example tree");
+    };
+    assert_eq!(example[0], ("rule".to_string(), 0_i64.into()));
+    assert_eq!(example[1], ("text".to_string(), "bbabbbbaabaabba".into()));
+}
+
+#[test]
+fn undefined_rules() {
+    let e = Solution::parse("0: 1 2\n1: \"a\"\n\nab\n").unwrap_err();
+    assert_eq!(e.to_string(), "1:6: rule 2 is not defined: '2'");
+    assert!(Solution::parse("1: \"a\"\n\na\n").is_err());
+
+    // Part 1's rules need not define those part 2 refers to, nor messages be ASCII
+    let mut solution = Solution::parse("0: 1 1\n1: \"é\"\n\néé\néa\n").unwrap();
+    solution.analyse();
+    assert_eq!(solution.answer_part1(), Answer::Integer(1));
+    assert!(!solution.answer_part2().is_solved());
+}
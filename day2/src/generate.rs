@@ -0,0 +1,27 @@
+use crate::Password;
+use rand::Rng;
+use std::io::Write;
+
+/// `size` password policies, each with a password that may or may not satisfy it.
+pub fn input(size: usize, rng: &mut impl Rng, out: &mut dyn Write) -> anyhow::Result<()> {
+    for _ in 0..size {
+        let req_min = rng.gen_range(1..10);
+        let req_max = rng.gen_range(req_min + 1..=20);
+        let req_char = rng.gen_range('a'..='z');
+        // Favour the required character, so that some passwords are valid
+        let password = (0..rng.gen_range(1..=20))
+            .map(|_| match rng.gen_bool(0.4) {
+                true => req_char,
+                false => rng.gen_range('a'..='z'),
+            })
+            .collect();
+        let password = Password {
+            req_min,
+            req_max,
+            req_char,
+            password,
+        };
+        writeln!(out, "{}", password)?;
+    }
+    Ok(())
+}
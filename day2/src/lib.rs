@@ -1,23 +1,12 @@
-use anyhow::Context;
+use puzzle::{check_lines, parse_at, Answer, ParseError, Puzzle};
+use rand::rngs::StdRng;
 use regex::Regex;
 use std::collections::HashMap;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
-use std::num::ParseIntError;
+use std::fmt::Display;
+use std::io::{BufRead, Write};
 use std::str::FromStr;
 
-pub fn load(filename: &str) -> anyhow::Result<Solution> {
-    let file = File::open(filename).context(format!("loading '{}'", filename))?;
-
-    let reader = BufReader::new(file);
-
-    let mut solution = Solution::default();
-    for line in reader.lines() {
-        let line = line?;
-        solution.add(Password::from_str(&line).unwrap());
-    }
-    Ok(solution)
-}
+mod generate;
 
 #[derive(Debug, Default)]
 pub struct Solution {
@@ -26,8 +15,31 @@ pub struct Solution {
     answer2: Option<i64>,
 }
 
-impl Solution {
-    pub fn analyse(&mut self) {
+impl Puzzle for Solution {
+    const YEAR: u32 = 2020;
+    const DAY: u32 = 2;
+    const TITLE: &'static str = "Password Philosophy";
+    const VERSION: &'static str = env!("CARGO_PKG_VERSION");
+
+    fn from_reader(reader: impl BufRead) -> anyhow::Result<Self> {
+        let mut solution = Solution::default();
+        for (idx, line) in reader.lines().enumerate() {
+            let line = line?;
+            let password = Password::from_str(&line).map_err(|e| e.at_line(idx + 1))?;
+            solution.add(password);
+        }
+        Ok(solution)
+    }
+
+    fn check(input: &str) -> Vec<ParseError> {
+        check_lines(input, |line| Password::from_str(line).map(drop))
+    }
+
+    fn generate(size: usize, rng: &mut StdRng, out: &mut dyn Write) -> anyhow::Result<()> {
+        generate::input(size, rng, out)
+    }
+
+    fn analyse(&mut self) {
         self.answer1 = None;
         let mut num_valid_part1 = 0;
         let mut num_valid_part2 = 0;
@@ -62,12 +74,12 @@ impl Solution {
         self.answer2 = Some(num_valid_part2);
     }
 
-    pub fn answer_part1(&self) -> Option<i64> {
-        self.answer1
+    fn answer_part1(&self) -> Answer {
+        self.answer1.into()
     }
 
-    pub fn answer_part2(&self) -> Option<i64> {
-        self.answer2
+    fn answer_part2(&self) -> Answer {
+        self.answer2.into()
     }
 }
 
@@ -77,7 +89,7 @@ impl Solution {
     }
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, PartialEq)]
 struct Password {
     req_min: usize,
     req_max: usize,
@@ -86,15 +98,35 @@ struct Password {
 }
 
 impl FromStr for Password {
-    type Err = ParseIntError;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let re =
             Regex::new(r"^(?P<min_req>\d+)-(?P<max_req>\d+) (?P<req_char>.): (?P<password>.*)$")
                 .unwrap();
-        let capt = re.captures(s).unwrap();
-        let req_min = capt.name("min_req").unwrap().as_str().parse().unwrap();
-        let req_max = capt.name("max_req").unwrap().as_str().parse().unwrap();
+        let capt = re
+            .captures(s)
+            .ok_or_else(|| ParseError::new("expected '<min>-<max> <char>: <password>'", s, 0))?;
+        let min_req = capt.name("min_req").unwrap();
+        let req_min = parse_at(min_req.as_str(), min_req.start())?;
+        let max_req = capt.name("max_req").unwrap();
+        let req_max = parse_at(max_req.as_str(), max_req.start())?;
+        // Both are 1-based positions, as well as counts
+        if req_min == 0 {
+            return Err(ParseError::new(
+                "expected a position of at least 1",
+                min_req.as_str(),
+                min_req.start(),
+            ));
+        }
+        if req_min > req_max {
+            let range = &s[..max_req.end()];
+            return Err(ParseError::new(
+                "expected <min> to be at most <max>",
+                range,
+                0,
+            ));
+        }
         let req_char = capt
             .name("req_char")
             .unwrap()
@@ -112,3 +144,45 @@ impl FromStr for Password {
         })
     }
 }
+
+impl Display for Password {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}-{} {}: {}",
+            self.req_min, self.req_max, self.req_char, self.password
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn password() -> impl Strategy<Value = Password> {
+        (1..usize::MAX, any::<usize>(), "[^\n]", "[^\n]*").prop_map(
+            |(req_min, req_max, req_char, password)| Password {
+                req_min,
+                req_max: req_max.max(req_min),
+                req_char: req_char.chars().next().unwrap(),
+                password,
+            },
+        )
+    }
+
+    #[test]
+    fn parse_invalid_range() {
+        let e = Password::from_str("0-3 a: abc").unwrap_err();
+        assert_eq!((e.column, e.text.as_str()), (1, "0"));
+        let e = Password::from_str("4-3 a: abc").unwrap_err();
+        assert_eq!((e.column, e.text.as_str()), (1, "4-3"));
+    }
+
+    proptest! {
+        #[test]
+        fn display_round_trip(password in password()) {
+            prop_assert_eq!(Password::from_str(&password.to_string()).unwrap(), password);
+        }
+    }
+}
@@ -0,0 +1,10 @@
+use day2::Solution;
+use puzzle::{Answer, Puzzle};
+
+#[test]
+fn example() {
+    let mut solution = Solution::parse(include_str!("../../inputs/2020/input.day2.small")).unwrap();
+    solution.analyse();
+    assert_eq!(solution.answer_part1(), Answer::Integer(2));
+    assert_eq!(solution.answer_part2(), Answer::Integer(1));
+}
@@ -0,0 +1,16 @@
+use rand::Rng;
+use std::io::Write;
+
+/// A slope `size` rows tall and 31 columns wide, a quarter of it trees.
+pub fn input(size: usize, rng: &mut impl Rng, out: &mut dyn Write) -> anyhow::Result<()> {
+    for _ in 0..size {
+        let row: String = (0..31)
+            .map(|_| match rng.gen_bool(0.25) {
+                true => '#',
+                false => '.',
+            })
+            .collect();
+        writeln!(out, "{}", row)?;
+    }
+    Ok(())
+}
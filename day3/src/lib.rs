@@ -1,76 +1,126 @@
-use anyhow::Context;
-use std::cmp::max;
-use std::collections::HashMap;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
-
-pub fn load(filename: &str) -> anyhow::Result<Solution> {
-    let file = File::open(filename).context(format!("loading '{}'", filename))?;
-
-    let mut solution = Solution::default();
-    let reader = BufReader::new(file);
-    for (y, line) in reader.lines().enumerate() {
-        for (x, c) in line?.chars().enumerate() {
-            solution.set(x, y, c);
-        }
-    }
-    Ok(solution)
-}
+use grid::{Cell, Edge, Grid};
+use puzzle::{Answer, ParseError, Part, Puzzle};
+use rand::rngs::StdRng;
+use std::collections::HashSet;
+use std::io::{BufRead, Write};
+
+mod generate;
+
+/// The slopes checked by part 2, as steps right and down.
+const SLOPES: [(i64, i64); 5] = [(1, 1), (3, 1), (5, 1), (7, 1), (1, 2)];
 
 #[derive(Debug, Default)]
 pub struct Solution {
-    map: HashMap<(usize, usize), char>,
+    map: Grid<Square>,
     answer_part1: Option<i64>,
     answer_part2: Option<i64>,
-
-    max_x: usize,
-    max_y: usize,
 }
 
-impl Solution {
-    pub fn analyse(&mut self) {
+impl Puzzle for Solution {
+    const YEAR: u32 = 2020;
+    const DAY: u32 = 3;
+    const TITLE: &'static str = "Toboggan Trajectory";
+    const VERSION: &'static str = env!("CARGO_PKG_VERSION");
+
+    fn from_reader(reader: impl BufRead) -> anyhow::Result<Self> {
+        // The slope repeats to the right, but ends at the bottom
+        let map = Grid::from_reader(reader)?.with_edges(Edge::Wrap, Edge::Clip);
+        Ok(Solution {
+            map,
+            ..Default::default()
+        })
+    }
+
+    fn check(input: &str) -> Vec<ParseError> {
+        Grid::<Square>::check(input)
+    }
+
+    fn generate(size: usize, rng: &mut StdRng, out: &mut dyn Write) -> anyhow::Result<()> {
+        generate::input(size, rng, out)
+    }
+
+    fn analyse(&mut self) {
         self.answer_part1 = Some(self.hit_trees(3, 1));
 
         let mut hit_trees = 1;
-        for (dx, dy) in [(1, 1), (3, 1), (5, 1), (7, 1), (1, 2)] {
+        for (dx, dy) in SLOPES {
             hit_trees *= self.hit_trees(dx, dy);
         }
         self.answer_part2 = Some(hit_trees);
     }
 
-    pub fn answer_part1(&self) -> Option<i64> {
-        self.answer_part1
+    fn answer_part1(&self) -> Answer {
+        self.answer_part1.into()
     }
 
-    pub fn answer_part2(&self) -> Option<i64> {
-        self.answer_part2
+    fn answer_part2(&self) -> Answer {
+        self.answer_part2.into()
+    }
+
+    /// The map with each square on the part's paths marked, `O` if open and `X` if a tree.
+    fn render(&self, part: Part) -> Option<String> {
+        let slopes = match part {
+            Part::One => &SLOPES[1..2],
+            Part::Two => &SLOPES[..],
+        };
+        let visited: HashSet<(usize, usize)> = slopes
+            .iter()
+            .flat_map(|&(dx, dy)| self.path(dx, dy).map(|(point, _)| point))
+            .collect();
+        let mut picture = String::new();
+        for ((x, y), &square) in self.map.iter() {
+            if x == 0 && y > 0 {
+                picture.push('\n');
+            }
+            picture.push(match (visited.contains(&(x, y)), square) {
+                (true, Square::Open) => 'O',
+                (true, Square::Tree) => 'X',
+                (false, square) => square.to_char(),
+            });
+        }
+        picture.push('\n');
+        Some(picture)
     }
 }
 
 impl Solution {
-    fn set(&mut self, x: usize, y: usize, c: char) {
-        *self.map.entry((x, y)).or_default() = c;
-        self.max_x = max(self.max_x, x);
-        self.max_y = max(self.max_y, y);
+    fn hit_trees(&self, dx: i64, dy: i64) -> i64 {
+        self.path(dx, dy)
+            .filter(|&(_, &square)| square == Square::Tree)
+            .count() as i64
     }
 
-    fn hit_trees(&self, dx: usize, dy: usize) -> i64 {
-        let mut x = 0;
-        let mut y = 0;
-        let mut hit_trees = 0;
-        loop {
-            match self.map.get(&(x, y)) {
-                Some('#') => hit_trees += 1,
-                Some('.') => {}
-                _ => unreachable!(),
-            }
-            x += dx;
-            x %= self.max_x + 1;
-            y += dy;
-            if y > self.max_y {
-                break;
-            }
+    /// The squares passed through from the top left, in steps of `(dx, dy)`, until the bottom.
+    fn path(&self, dx: i64, dy: i64) -> impl Iterator<Item = ((usize, usize), &Square)> {
+        self.map
+            .get(0, 0)
+            .map(|square| ((0, 0), square))
+            .into_iter()
+            .chain(self.map.line_of_sight(0, 0, (dx, dy)))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Square {
+    Open,
+    Tree,
+}
+
+impl Cell for Square {
+    const EXPECTED: &'static str = "'#' or '.'";
+
+    fn from_char(c: char) -> Option<Self> {
+        match c {
+            '.' => Some(Square::Open),
+            '#' => Some(Square::Tree),
+            _ => None,
+        }
+    }
+
+    fn to_char(self) -> char {
+        match self {
+            Square::Open => '.',
+            Square::Tree => '#',
         }
-        hit_trees
     }
 }
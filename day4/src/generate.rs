@@ -0,0 +1,60 @@
+use rand::seq::SliceRandom;
+use rand::Rng;
+use std::io::Write;
+
+const EYE_COLORS: [&str; 7] = ["amb", "blu", "brn", "gry", "grn", "hzl", "oth"];
+
+/// `size` passports, mostly complete and mostly valid, with their fields shuffled and spread
+/// over a few lines.
+pub fn input(size: usize, rng: &mut impl Rng, out: &mut dyn Write) -> anyhow::Result<()> {
+    for idx in 0..size {
+        if idx > 0 {
+            writeln!(out)?;
+        }
+        let mut fields = Vec::new();
+        for key in ["byr", "iyr", "eyr", "hgt", "hcl", "ecl", "pid", "cid"] {
+            if rng.gen_bool(if key == "cid" { 0.5 } else { 0.95 }) {
+                let valid = rng.gen_bool(0.9);
+                fields.push(format!("{}:{}", key, value(key, valid, rng)));
+            }
+        }
+        fields.shuffle(rng);
+        for (idx, field) in fields.iter().enumerate() {
+            let separator = match idx {
+                0 => "",
+                _ if rng.gen_bool(0.3) => "\n",
+                _ => " ",
+            };
+            write!(out, "{}{}", separator, field)?;
+        }
+        writeln!(out)?;
+    }
+    Ok(())
+}
+
+fn value(key: &str, valid: bool, rng: &mut impl Rng) -> String {
+    match (key, valid) {
+        ("byr", true) => rng.gen_range(1920..=2002).to_string(),
+        ("byr", false) => rng.gen_range(1880..1920).to_string(),
+        ("iyr", true) => rng.gen_range(2010..=2020).to_string(),
+        ("iyr", false) => rng.gen_range(1990..2010).to_string(),
+        ("eyr", true) => rng.gen_range(2020..=2030).to_string(),
+        ("eyr", false) => rng.gen_range(2031..2050).to_string(),
+        ("hgt", true) if rng.gen_bool(0.5) => format!("{}cm", rng.gen_range(150..=193)),
+        ("hgt", true) => format!("{}in", rng.gen_range(59..=76)),
+        ("hgt", false) => rng.gen_range(59..=193).to_string(),
+        ("hcl", true) => format!("#{:06x}", rng.gen_range(0..0x1000000)),
+        ("hcl", false) => format!("{:06x}", rng.gen_range(0..0x1000000)),
+        ("ecl", true) => EYE_COLORS.choose(rng).unwrap().to_string(),
+        ("ecl", false) => "zzz".to_string(),
+        ("pid", true) => digits(9, rng),
+        ("pid", false) => digits(10, rng),
+        _ => digits(3, rng),
+    }
+}
+
+fn digits(count: usize, rng: &mut impl Rng) -> String {
+    (0..count)
+        .map(|_| char::from_digit(rng.gen_range(0..10), 10).unwrap())
+        .collect()
+}
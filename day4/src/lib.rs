@@ -1,11 +1,13 @@
-use anyhow::Context;
+use puzzle::{check_lines, Answer, ParseError, Puzzle};
+use rand::rngs::StdRng;
 use std::collections::HashMap;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::io::{BufRead, Write};
 #[macro_use]
 extern crate lazy_static;
 use log::{debug, error};
 
+mod generate;
+
 lazy_static! {
     static ref REQUIRED_PASSPORT_FIELDS: HashMap<&'static str, regex::Regex> = {
         let mut m = HashMap::new();
@@ -29,25 +31,18 @@ lazy_static! {
     };
 }
 
-pub fn load(filename: &str) -> anyhow::Result<Solution> {
-    let file = File::open(filename).context(format!("loading '{}'", filename))?;
-
-    let mut solution = Solution::default();
-    let reader = BufReader::new(file);
-    let mut passport = String::new();
-    for line in reader.lines() {
-        let line = line?;
-        let line = line.trim();
-        if line.is_empty() {
-            solution.add_passport(&passport);
-            passport = String::new();
-        } else {
-            passport.push(' ');
-            passport.push_str(line);
-        }
-    }
-    solution.add_passport(&passport);
-    Ok(solution)
+/// The fields of one line of a passport, each a `key:value` pair, separated by whitespace.
+fn fields(line: &str) -> Result<Vec<(&str, &str)>, ParseError> {
+    line.split_whitespace()
+        .map(|field| {
+            field.split_once(':').ok_or_else(|| {
+                // `field` is a slice of `line`, so this is where it starts
+                let start = field.as_ptr() as usize - line.as_ptr() as usize;
+                let column = line[..start].chars().count();
+                ParseError::new("expected 'key:value'", field, column)
+            })
+        })
+        .collect()
 }
 
 #[derive(Debug, Default)]
@@ -57,8 +52,39 @@ pub struct Solution {
     answer_part2: Option<i64>,
 }
 
-impl Solution {
-    pub fn analyse(&mut self) {
+impl Puzzle for Solution {
+    const YEAR: u32 = 2020;
+    const DAY: u32 = 4;
+    const TITLE: &'static str = "Passport Processing";
+    const VERSION: &'static str = env!("CARGO_PKG_VERSION");
+
+    fn from_reader(reader: impl BufRead) -> anyhow::Result<Self> {
+        let mut solution = Solution::default();
+        let mut passport = HashMap::new();
+        for (idx, line) in reader.lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                solution.passports.push(std::mem::take(&mut passport));
+                continue;
+            }
+            let fields = fields(&line).map_err(|e| e.at_line(idx + 1))?;
+            for (key, value) in fields {
+                passport.insert(key.to_string(), value.to_string());
+            }
+        }
+        solution.passports.push(passport);
+        Ok(solution)
+    }
+
+    fn check(input: &str) -> Vec<ParseError> {
+        check_lines(input, |line| fields(line).map(|_| ()))
+    }
+
+    fn generate(size: usize, rng: &mut StdRng, out: &mut dyn Write) -> anyhow::Result<()> {
+        generate::input(size, rng, out)
+    }
+
+    fn analyse(&mut self) {
         let num_valid_part1 = self
             .passports
             .iter()
@@ -74,30 +100,16 @@ impl Solution {
         self.answer_part2 = Some(num_valid_part2 as i64);
     }
 
-    pub fn answer_part1(&self) -> Option<i64> {
-        self.answer_part1
+    fn answer_part1(&self) -> Answer {
+        self.answer_part1.into()
     }
 
-    pub fn answer_part2(&self) -> Option<i64> {
-        self.answer_part2
+    fn answer_part2(&self) -> Answer {
+        self.answer_part2.into()
     }
 }
 
 impl Solution {
-    fn add_passport(&mut self, passport: &str) {
-        let passport = passport
-            .trim()
-            .split_whitespace()
-            .map(|s| {
-                let pos = s.find(':').unwrap();
-                let first = s[..pos].to_string();
-                let rest = s[(pos + 1)..].to_string();
-                (first, rest)
-            })
-            .collect::<HashMap<_, _>>();
-        self.passports.push(passport);
-    }
-
     fn is_invalid_part1(&self, passport: &HashMap<String, String>) -> bool {
         REQUIRED_PASSPORT_FIELDS
             .keys()
@@ -179,4 +191,14 @@ mod tests {
             assert_eq!(result, expected);
         }
     }
+
+    #[test]
+    fn fields_split_on_whitespace() {
+        assert_eq!(
+            fields(" ecl:gry\tpid:860033327 ").unwrap(),
+            vec![("ecl", "gry"), ("pid", "860033327")]
+        );
+        let e = Solution::parse("hcl:#fffffd\né:1 ecl:gry\tpid\n").unwrap_err();
+        assert_eq!(e.to_string(), "2:13: expected 'key:value': 'pid'");
+    }
 }
@@ -0,0 +1,27 @@
+use day4::Solution;
+use puzzle::{Answer, Puzzle};
+
+#[test]
+fn example() {
+    let mut solution = Solution::parse(include_str!("../../inputs/2020/input.day4.small")).unwrap();
+    solution.analyse();
+    assert_eq!(solution.answer_part1(), Answer::Integer(2));
+    assert_eq!(solution.answer_part2(), Answer::Integer(2));
+}
+
+#[test]
+fn invalid_passports() {
+    let mut solution =
+        Solution::parse(include_str!("../../inputs/2020/input.day4.invalid")).unwrap();
+    solution.analyse();
+    assert_eq!(solution.answer_part1(), Answer::Integer(4));
+    assert_eq!(solution.answer_part2(), Answer::Integer(0));
+}
+
+#[test]
+fn valid_passports() {
+    let mut solution = Solution::parse(include_str!("../../inputs/2020/input.day4.valid")).unwrap();
+    solution.analyse();
+    assert_eq!(solution.answer_part1(), Answer::Integer(4));
+    assert_eq!(solution.answer_part2(), Answer::Integer(4));
+}
@@ -0,0 +1,30 @@
+use anyhow::ensure;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use std::io::Write;
+
+/// `size` boarding passes for a block of consecutive seats, missing one seat in the middle.
+///
+/// The plane only has 1024 seats, so `size` can be at most 1023.
+pub fn input(size: usize, rng: &mut impl Rng, out: &mut dyn Write) -> anyhow::Result<()> {
+    ensure!(
+        (2..=1023).contains(&size),
+        "expected between 2 and 1023 boarding passes, as the plane has 1024 seats"
+    );
+    let first = rng.gen_range(0..1024 - size);
+    let missing = first + rng.gen_range(1..size);
+    let mut seats = (first..=first + size)
+        .filter(|&seat| seat != missing)
+        .collect::<Vec<_>>();
+    seats.shuffle(rng);
+    for seat in seats {
+        let row = format!("{:07b}", seat >> 3)
+            .replace('0', "F")
+            .replace('1', "B");
+        let column = format!("{:03b}", seat & 7)
+            .replace('0', "L")
+            .replace('1', "R");
+        writeln!(out, "{}{}", row, column)?;
+    }
+    Ok(())
+}
@@ -1,20 +1,24 @@
-use anyhow::Context;
 use log::debug;
+use puzzle::{check_lines, Answer, ParseError, Puzzle};
+use rand::rngs::StdRng;
 use std::collections::HashSet;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::io::{BufRead, Write};
 
-pub fn load(filename: &str) -> anyhow::Result<Solution> {
-    let file = File::open(filename).context(format!("loading '{}'", filename))?;
+mod generate;
 
-    let mut solution = Solution::default();
-    let reader = BufReader::new(file);
-    for line in reader.lines() {
-        let line = line?;
-        let line = line.trim();
-        solution.add_pass(line);
+/// A boarding pass is 7 row characters (`F`/`B`) followed by 3 column characters (`L`/`R`).
+fn check_pass(pass: &str) -> Result<(), ParseError> {
+    if pass.len() != 10 {
+        return Err(ParseError::new("expected 10 characters", pass, 0));
     }
-    Ok(solution)
+    for (idx, c) in pass.char_indices() {
+        match (idx, c) {
+            (0..=6, 'F' | 'B') | (7..=9, 'L' | 'R') => {}
+            (0..=6, _) => return Err(ParseError::new("expected 'F' or 'B'", &c.to_string(), idx)),
+            _ => return Err(ParseError::new("expected 'L' or 'R'", &c.to_string(), idx)),
+        }
+    }
+    Ok(())
 }
 
 #[derive(Debug, Default)]
@@ -24,8 +28,32 @@ pub struct Solution {
     answer_part2: Option<i64>,
 }
 
-impl Solution {
-    pub fn analyse(&mut self) {
+impl Puzzle for Solution {
+    const YEAR: u32 = 2020;
+    const DAY: u32 = 5;
+    const TITLE: &'static str = "Binary Boarding";
+    const VERSION: &'static str = env!("CARGO_PKG_VERSION");
+
+    fn from_reader(reader: impl BufRead) -> anyhow::Result<Self> {
+        let mut solution = Solution::default();
+        for (idx, line) in reader.lines().enumerate() {
+            let line = line?;
+            let line = line.trim();
+            check_pass(line).map_err(|e| e.at_line(idx + 1))?;
+            solution.add_pass(line);
+        }
+        Ok(solution)
+    }
+
+    fn check(input: &str) -> Vec<ParseError> {
+        check_lines(input, |line| check_pass(line.trim()))
+    }
+
+    fn generate(size: usize, rng: &mut StdRng, out: &mut dyn Write) -> anyhow::Result<()> {
+        generate::input(size, rng, out)
+    }
+
+    fn analyse(&mut self) {
         let ids = self
             .seat_codes
             .iter()
@@ -40,12 +68,12 @@ impl Solution {
         }
     }
 
-    pub fn answer_part1(&self) -> Option<i64> {
-        self.answer_part1
+    fn answer_part1(&self) -> Answer {
+        self.answer_part1.into()
     }
 
-    pub fn answer_part2(&self) -> Option<i64> {
-        self.answer_part2
+    fn answer_part2(&self) -> Answer {
+        self.answer_part2.into()
     }
 }
 
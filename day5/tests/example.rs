@@ -0,0 +1,10 @@
+use day5::Solution;
+use puzzle::{Answer, Puzzle};
+
+#[test]
+fn example() {
+    let mut solution = Solution::parse(include_str!("../../inputs/2020/input.day5.small")).unwrap();
+    solution.analyse();
+    assert_eq!(solution.answer_part1(), Answer::Integer(820));
+    assert!(!solution.answer_part2().is_solved());
+}
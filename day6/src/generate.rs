@@ -0,0 +1,25 @@
+use rand::Rng;
+use std::io::Write;
+
+/// `size` groups of one to five people, each answering yes to a random set of questions.
+pub fn input(size: usize, rng: &mut impl Rng, out: &mut dyn Write) -> anyhow::Result<()> {
+    for idx in 0..size {
+        if idx > 0 {
+            writeln!(out)?;
+        }
+        // Questions everyone in the group answers yes to, so that part 2 has something to count
+        let shared: Vec<bool> = (0..26).map(|_| rng.gen_bool(0.2)).collect();
+        for _ in 0..rng.gen_range(1..=5) {
+            let mut answers: String = ('a'..='z')
+                .zip(&shared)
+                .filter(|&(_, &shared)| shared || rng.gen_bool(0.2))
+                .map(|(question, _)| question)
+                .collect();
+            if answers.is_empty() {
+                answers.push(rng.gen_range('a'..='z'));
+            }
+            writeln!(out, "{}", answers)?;
+        }
+    }
+    Ok(())
+}
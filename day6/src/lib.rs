@@ -1,28 +1,10 @@
-use anyhow::Context;
 use itertools::Itertools;
+use puzzle::{Answer, Puzzle};
+use rand::rngs::StdRng;
 use std::collections::HashMap;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::io::{BufRead, Write};
 
-pub fn load(filename: &str) -> anyhow::Result<Solution> {
-    let file = File::open(filename).context(format!("loading '{}'", filename))?;
-
-    let mut solution = Solution::default();
-    let reader = BufReader::new(file);
-    let mut group = Vec::new();
-    for line in reader.lines() {
-        let line = line?;
-        let line = line.trim();
-        if line.is_empty() {
-            solution.add_group(group);
-            group = Vec::new();
-        } else {
-            group.push(line.to_string());
-        }
-    }
-    solution.add_group(group);
-    Ok(solution)
-}
+mod generate;
 
 #[derive(Debug, Default)]
 pub struct Solution {
@@ -31,8 +13,34 @@ pub struct Solution {
     answer_part2: Option<i64>,
 }
 
-impl Solution {
-    pub fn analyse(&mut self) {
+impl Puzzle for Solution {
+    const YEAR: u32 = 2020;
+    const DAY: u32 = 6;
+    const TITLE: &'static str = "Custom Customs";
+    const VERSION: &'static str = env!("CARGO_PKG_VERSION");
+
+    fn from_reader(reader: impl BufRead) -> anyhow::Result<Self> {
+        let mut solution = Solution::default();
+        let mut group = Vec::new();
+        for line in reader.lines() {
+            let line = line?;
+            let line = line.trim();
+            if line.is_empty() {
+                solution.add_group(group);
+                group = Vec::new();
+            } else {
+                group.push(line.to_string());
+            }
+        }
+        solution.add_group(group);
+        Ok(solution)
+    }
+
+    fn generate(size: usize, rng: &mut StdRng, out: &mut dyn Write) -> anyhow::Result<()> {
+        generate::input(size, rng, out)
+    }
+
+    fn analyse(&mut self) {
         let part1: usize = self
             .groups
             .iter()
@@ -49,12 +57,12 @@ impl Solution {
         self.answer_part2 = Some(part2 as i64);
     }
 
-    pub fn answer_part1(&self) -> Option<i64> {
-        self.answer_part1
+    fn answer_part1(&self) -> Answer {
+        self.answer_part1.into()
     }
 
-    pub fn answer_part2(&self) -> Option<i64> {
-        self.answer_part2
+    fn answer_part2(&self) -> Answer {
+        self.answer_part2.into()
     }
 }
 
@@ -81,11 +89,3 @@ impl Solution {
         group.iter().flat_map(|v| v.chars()).unique().collect()
     }
 }
-
-#[cfg(test)]
-mod tests {
-    #[ctor::ctor]
-    fn init() {
-        env_logger::init();
-    }
-}
@@ -0,0 +1,102 @@
+use crate::Rule;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use std::io::Write;
+
+const ADJECTIVES: [&str; 16] = [
+    "bright", "clear", "dark", "dim", "dotted", "drab", "dull", "faded", "light", "mirrored",
+    "muted", "pale", "plaid", "posh", "striped", "vibrant",
+];
+const HUES: [&str; 16] = [
+    "aqua", "beige", "black", "blue", "bronze", "coral", "crimson", "cyan", "fuchsia", "gray",
+    "green", "indigo", "lavender", "lime", "maroon", "olive",
+];
+
+/// Rules nesting bags `size` deep inside the shiny gold bag, and `size` deep outside it.
+///
+/// Inside, each bag holds a single bag of the next level, along with a few bags that hold no
+/// others, so that the total stays small however deep the nesting goes. Outside, each of four
+/// bags per level holds a few from the level below.
+pub fn input(size: usize, rng: &mut impl Rng, out: &mut dyn Write) -> anyhow::Result<()> {
+    let mut colors = (0..).map(color);
+    let mut next = || colors.next().unwrap();
+    let empty: Vec<String> = (0..8).map(|_| next()).collect();
+    let inside: Vec<String> = (0..size).map(|_| next()).collect();
+    let outside: Vec<Vec<String>> = (0..size)
+        .map(|_| (0..4).map(|_| next()).collect())
+        .collect();
+
+    let mut rules: Vec<Rule> = empty
+        .iter()
+        .map(|source| Rule {
+            source: source.clone(),
+            contains: vec![(0, "no other".to_string())],
+        })
+        .collect();
+
+    let mut bags = std::iter::once("shiny gold".to_string())
+        .chain(inside)
+        .peekable();
+    while let Some(source) = bags.next() {
+        let mut contains = some_of(&empty, rng);
+        if let Some(inner) = bags.peek() {
+            contains.push((1, inner.clone()));
+        }
+        if contains.is_empty() {
+            contains.push((0, "no other".to_string()));
+        }
+        contains.shuffle(rng);
+        rules.push(Rule { source, contains });
+    }
+
+    let mut below = vec!["shiny gold".to_string()];
+    for (level, colors) in outside.iter().rev().enumerate() {
+        for (idx, source) in colors.iter().enumerate() {
+            let count = rng.gen_range(1..=below.len().min(3));
+            let mut contains: Vec<(i64, String)> = below
+                .choose_multiple(rng, count)
+                .map(|color| (rng.gen_range(1..=5), color.clone()))
+                .collect();
+            // Make sure something holds the shiny gold bag
+            if level == 0 && idx == 0 && contains.iter().all(|(_, c)| c != "shiny gold") {
+                contains.push((rng.gen_range(1..=5), "shiny gold".to_string()));
+            }
+            contains.extend(some_of(&empty, rng));
+            contains.shuffle(rng);
+            rules.push(Rule {
+                source: source.clone(),
+                contains,
+            });
+        }
+        below = colors.clone();
+    }
+
+    rules.shuffle(rng);
+    for rule in rules {
+        writeln!(out, "{}", rule)?;
+    }
+    Ok(())
+}
+
+/// Up to two of the bags that hold no others.
+fn some_of(empty: &[String], rng: &mut impl Rng) -> Vec<(i64, String)> {
+    let count = rng.gen_range(0..=2);
+    let colors: Vec<&String> = empty.choose_multiple(rng, count).collect();
+    colors
+        .into_iter()
+        .map(|color| (rng.gen_range(1..=4), color.clone()))
+        .collect()
+}
+
+/// A distinct two word color for every index, never "shiny gold".
+fn color(idx: usize) -> String {
+    let adjective = ADJECTIVES[idx % ADJECTIVES.len()];
+    let hue = HUES[(idx / ADJECTIVES.len()) % HUES.len()];
+    let mut round = idx / (ADJECTIVES.len() * HUES.len());
+    let mut suffix = String::new();
+    while round > 0 {
+        suffix.push((b'a' + (round % 26) as u8) as char);
+        round /= 26;
+    }
+    format!("{}{} {}", adjective, suffix, hue)
+}
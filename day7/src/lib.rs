@@ -1,24 +1,14 @@
 use log::debug;
+use puzzle::{check_lines, parse_at, Answer, ParseError, Puzzle};
+use rand::rngs::StdRng;
 use regex::Regex;
 use std::collections::HashMap;
 use std::collections::HashSet;
-use std::fmt::Debug;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
-use std::num::ParseIntError;
+use std::fmt::{Debug, Display};
+use std::io::{BufRead, Write};
 use std::str::FromStr;
 
-pub fn load(filename: &str) -> anyhow::Result<Solution> {
-    let file = File::open(filename)?;
-
-    let reader = BufReader::new(file);
-    let mut solution = Solution::default();
-    for line in reader.lines() {
-        let line = line?;
-        solution.add(Rule::from_str(&line)?);
-    }
-    Ok(solution)
-}
+mod generate;
 
 #[derive(Debug, Default)]
 pub struct Solution {
@@ -28,11 +18,6 @@ pub struct Solution {
 }
 
 impl Solution {
-    pub fn analyse(&mut self) {
-        self.answer_part1 = self.analyse_part1();
-        self.answer_part2 = self.analyse_part2();
-    }
-
     fn analyse_part1(&self) -> Option<i64> {
         let mut reverse_rules = HashMap::new();
         for rule in &self.rules {
@@ -87,13 +72,43 @@ impl Solution {
         // Remove 1 as shiny gold was visited first
         Some(total - 1)
     }
+}
+
+impl Puzzle for Solution {
+    const YEAR: u32 = 2020;
+    const DAY: u32 = 7;
+    const TITLE: &'static str = "Handy Haversacks";
+    const VERSION: &'static str = env!("CARGO_PKG_VERSION");
+
+    fn from_reader(reader: impl BufRead) -> anyhow::Result<Self> {
+        let mut solution = Solution::default();
+        for (idx, line) in reader.lines().enumerate() {
+            let line = line?;
+            let rule = Rule::from_str(&line).map_err(|e| e.at_line(idx + 1))?;
+            solution.add(rule);
+        }
+        Ok(solution)
+    }
+
+    fn check(input: &str) -> Vec<ParseError> {
+        check_lines(input, |line| Rule::from_str(line).map(drop))
+    }
+
+    fn generate(size: usize, rng: &mut StdRng, out: &mut dyn Write) -> anyhow::Result<()> {
+        generate::input(size, rng, out)
+    }
+
+    fn analyse(&mut self) {
+        self.answer_part1 = self.analyse_part1();
+        self.answer_part2 = self.analyse_part2();
+    }
 
-    pub fn answer_part1(&self) -> Option<i64> {
-        self.answer_part1
+    fn answer_part1(&self) -> Answer {
+        self.answer_part1.into()
     }
 
-    pub fn answer_part2(&self) -> Option<i64> {
-        self.answer_part2
+    fn answer_part2(&self) -> Answer {
+        self.answer_part2.into()
     }
 }
 
@@ -103,37 +118,83 @@ impl Solution {
     }
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, PartialEq)]
 struct Rule {
     source: String,
     contains: Vec<(i64, String)>,
 }
 
 impl FromStr for Rule {
-    type Err = ParseIntError;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
+        const SEPARATOR: &str = " bags contain ";
+
         debug!("{}", s);
         let r = Regex::new(r"^(?P<count>\d+)?(?P<color>.*) bags?\.?$").unwrap();
-        let mut i = s.split(" bags contain ");
-        let source = i.next().unwrap().to_string();
+        let (source, rest) = s
+            .split_once(SEPARATOR)
+            .ok_or_else(|| ParseError::new("expected '<color> bags contain ...'", s, 0))?;
+        let source = source.to_string();
         debug!("source: {}", source);
-        let contains = i
-            .flat_map(|s| s.split(", "))
-            .map(|s| {
-                let c = r.captures(s).unwrap();
-                let count = c
-                    .name("count")
-                    .map(|v| v.as_str().parse::<i64>().unwrap())
-                    .unwrap_or(0);
-                let color = c
-                    .name("color")
-                    .map(|v| v.as_str().trim().to_string())
-                    .unwrap();
-                (count, color)
-            })
-            .collect::<Vec<_>>();
+        let mut offset = source.len() + SEPARATOR.len();
+        let mut contains = Vec::new();
+        for s in rest.split(", ") {
+            let c = r
+                .captures(s)
+                .ok_or_else(|| ParseError::new("expected '<count> <color> bag(s)'", s, offset))?;
+            let count = match c.name("count") {
+                Some(v) => parse_at(v.as_str(), offset + v.start())?,
+                None => 0,
+            };
+            let color = c
+                .name("color")
+                .map(|v| v.as_str().trim().to_string())
+                .unwrap();
+            contains.push((count, color));
+            offset += s.len() + 2;
+        }
         debug!("  {:?}", contains);
         Ok(Self { source, contains })
     }
 }
+
+impl Display for Rule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} bags contain ", self.source)?;
+        for (idx, (count, color)) in self.contains.iter().enumerate() {
+            if idx > 0 {
+                write!(f, ", ")?;
+            }
+            // A count of zero is how "no other bags" is parsed
+            match count {
+                0 => write!(f, "{} bags", color)?,
+                1 => write!(f, "1 {} bag", color)?,
+                count => write!(f, "{} {} bags", count, color)?,
+            }
+        }
+        write!(f, ".")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn rule() -> impl Strategy<Value = Rule> {
+        let color = "[a-z]+ [a-z]+";
+        let contains = prop_oneof![
+            Just(vec![(0, "no other".to_string())]),
+            prop::collection::vec((1..100_i64, color), 1..5),
+        ];
+        (color, contains).prop_map(|(source, contains)| Rule { source, contains })
+    }
+
+    proptest! {
+        #[test]
+        fn display_round_trip(rule in rule()) {
+            prop_assert_eq!(Rule::from_str(&rule.to_string()).unwrap(), rule);
+        }
+    }
+}
@@ -0,0 +1,18 @@
+use day7::Solution;
+use puzzle::{Answer, Puzzle};
+
+#[test]
+fn example() {
+    let mut solution = Solution::parse(include_str!("../../inputs/2020/input.day7.small")).unwrap();
+    solution.analyse();
+    assert_eq!(solution.answer_part1(), Answer::Integer(4));
+    assert_eq!(solution.answer_part2(), Answer::Integer(32));
+}
+
+#[test]
+fn deep() {
+    let mut solution = Solution::parse(include_str!("../../inputs/2020/input.day7.deep")).unwrap();
+    solution.analyse();
+    assert_eq!(solution.answer_part1(), Answer::Integer(0));
+    assert_eq!(solution.answer_part2(), Answer::Integer(126));
+}
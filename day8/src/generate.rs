@@ -0,0 +1,37 @@
+use crate::Operation;
+use rand::Rng;
+use std::io::Write;
+
+/// A program of `size` instructions that runs from the start to a final jump back to the
+/// start, which is the only instruction whose repair lets it terminate.
+///
+/// Every other argument, whether of a jump or a no-op, stays within the program.
+pub fn input(size: usize, rng: &mut impl Rng, out: &mut dyn Write) -> anyhow::Result<()> {
+    let last = size.max(1) as isize - 1;
+    let mut program = Vec::new();
+    while (program.len() as isize) < last {
+        let idx = program.len() as isize;
+        match rng.gen_range(0..10) {
+            0..=4 => program.push(Operation::Acc(rng.gen_range(-99..=99))),
+            5..=7 => program.push(Operation::Nop(rng.gen_range(-idx..=last - idx))),
+            _ => {
+                // Jump forwards over instructions which are never run, unless it is repaired
+                let skip = rng.gen_range(1..=(last - idx).min(8));
+                program.push(Operation::Jmp(skip));
+                for idx in idx + 1..idx + skip {
+                    let arg = rng.gen_range(-idx..=last - idx);
+                    program.push(match rng.gen_range(0..3) {
+                        0 => Operation::Acc(rng.gen_range(-99..=99)),
+                        1 => Operation::Jmp(arg),
+                        _ => Operation::Nop(arg),
+                    });
+                }
+            }
+        }
+    }
+    program.push(Operation::Jmp(-last));
+    for operation in program {
+        writeln!(out, "{}", operation)?;
+    }
+    Ok(())
+}
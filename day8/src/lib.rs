@@ -1,43 +1,86 @@
-use log::debug;
+use puzzle::{check_lines, parse_at, Answer, Cancel, Explanation, ParseError, Part, Puzzle};
+use rand::rngs::StdRng;
 use std::collections::HashMap;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
-use std::num::ParseIntError;
+use std::fmt::Display;
+use std::io::{BufRead, Write};
 use std::str::FromStr;
+use tracing::{debug, debug_span, trace_span};
 
-pub fn load(filename: &str) -> anyhow::Result<Solution> {
-    let file = File::open(filename)?;
-
-    let reader = BufReader::new(file);
-
-    let mut solution = Solution::default();
-    for line in reader.lines() {
-        let line = line?;
-        let line = line.trim();
-        solution.add_operation(Operation::from_str(line)?);
-    }
-    Ok(solution)
-}
+mod generate;
 
 #[derive(Debug, Default)]
 pub struct Solution {
     program: Vec<Operation>,
-    answer_part1: Option<i64>,
-    answer_part2: Option<i64>,
+    cancel: Cancel,
+    /// The instruction about to run a second time, and the accumulator before it does.
+    answer_part1: Option<(usize, i64)>,
+    /// The instruction patched, and the accumulator once the program ends.
+    answer_part2: Option<(usize, i64)>,
 }
 
-impl Solution {
-    pub fn analyse(&mut self) {
-        self.answer_part1 = Some(self.analyse_part1());
-        self.answer_part2 = Some(self.analyse_part2());
+impl Puzzle for Solution {
+    const YEAR: u32 = 2020;
+    const DAY: u32 = 8;
+    const TITLE: &'static str = "Handheld Halting";
+    const VERSION: &'static str = env!("CARGO_PKG_VERSION");
+
+    fn from_reader(reader: impl BufRead) -> anyhow::Result<Self> {
+        let mut solution = Solution::default();
+        for (idx, line) in reader.lines().enumerate() {
+            let line = line?;
+            let line = line.trim();
+            let operation = Operation::from_str(line).map_err(|e| e.at_line(idx + 1))?;
+            solution.add_operation(operation);
+        }
+        Ok(solution)
+    }
+
+    fn check(input: &str) -> Vec<ParseError> {
+        check_lines(input, |line| Operation::from_str(line.trim()).map(drop))
     }
 
-    pub fn answer_part1(&self) -> Option<i64> {
-        self.answer_part1
+    fn generate(size: usize, rng: &mut StdRng, out: &mut dyn Write) -> anyhow::Result<()> {
+        generate::input(size, rng, out)
     }
 
-    pub fn answer_part2(&self) -> Option<i64> {
-        self.answer_part2
+    fn set_cancel(&mut self, cancel: Cancel) {
+        self.cancel = cancel;
+    }
+
+    fn analyse(&mut self) {
+        self.answer_part1 = self.analyse_part1();
+        self.answer_part2 = self.analyse_part2();
+    }
+
+    fn answer_part1(&self) -> Answer {
+        self.answer_part1.map(|(_, accumulator)| accumulator).into()
+    }
+
+    fn answer_part2(&self) -> Answer {
+        self.answer_part2.map(|(_, accumulator)| accumulator).into()
+    }
+
+    fn explain(&self, part: Part) -> Option<Explanation> {
+        let explanation = match part {
+            Part::One => {
+                let (repeated, accumulator) = self.answer_part1?;
+                Explanation::record([
+                    ("repeated", repeated.into()),
+                    ("operation", self.program[repeated].to_string().into()),
+                    ("accumulator", accumulator.into()),
+                ])
+            }
+            Part::Two => {
+                let (patched, accumulator) = self.answer_part2?;
+                Explanation::record([
+                    ("patched", patched.into()),
+                    ("was", self.program[patched].to_string().into()),
+                    ("now", self.program[patched].patched().to_string().into()),
+                    ("accumulator", accumulator.into()),
+                ])
+            }
+        };
+        Some(explanation)
     }
 }
 
@@ -46,82 +89,150 @@ impl Solution {
         self.program.push(operation);
     }
 
-    fn analyse_part1(&self) -> i64 {
-        use Operation::*;
-
+    /// The accumulator just before an instruction runs a second time, if one does before the
+    /// program jumps out of itself, along with that instruction.
+    fn analyse_part1(&self) -> Option<(usize, i64)> {
+        let _run = debug_span!("run").entered();
         let mut pc = 0_isize;
         let mut accumulator = 0;
         let mut count = HashMap::new();
         loop {
-            debug!("{} {:?} {}", pc, self.program[pc as usize], accumulator);
+            if self.cancel.is_cancelled() {
+                return None;
+            }
+            let idx = usize::try_from(pc).ok()?;
+            let operation = *self.program.get(idx)?;
             *count.entry(pc).or_insert(0) += 1;
             if count[&pc] > 1 {
-                return accumulator;
+                return Some((idx, accumulator));
             }
-            match self.program[pc as usize] {
-                Acc(v) => accumulator += v as i64,
-                Jmp(v) => pc += v - 1,
-                Nop(_v) => {}
-            };
-            pc += 1;
+            (pc, accumulator) = operation.run(pc, accumulator)?;
         }
     }
 
-    fn analyse_part2(&self) -> i64 {
-        use Operation::*;
-
+    /// The accumulator once the program ends, after patching the returned instruction.
+    fn analyse_part2(&self) -> Option<(usize, i64)> {
+        let _repair = debug_span!("repair").entered();
         for (id, _operation) in self.program.iter().enumerate() {
+            if self.cancel.is_cancelled() {
+                return None;
+            }
             let mut program = self.program.clone();
-            program[id] = match program[id] {
-                Acc(v) => Acc(v),
-                Jmp(v) => Nop(v),
-                Nop(v) => Jmp(v),
-            };
+            program[id] = program[id].patched();
+            let _run = trace_span!("run", patched = id).entered();
             let mut pc = 0_isize;
             let mut accumulator = 0;
             let mut count = HashMap::new();
             'inner: loop {
                 if pc == program.len() as isize {
-                    return accumulator;
+                    return Some((id, accumulator));
                 }
-                debug!("{} {:?} {}", pc, program[pc as usize], accumulator);
+                // Jumping anywhere else outside the program is no fix either
+                let operation = match usize::try_from(pc).ok().and_then(|pc| program.get(pc)) {
+                    Some(&operation) => operation,
+                    None => break 'inner,
+                };
                 *count.entry(pc).or_insert(0) += 1;
                 if count[&pc] > 1 {
                     break 'inner;
                 }
-                match program[pc as usize] {
-                    Acc(v) => accumulator += v as i64,
-                    Jmp(v) => pc += v - 1,
-                    Nop(_v) => {}
+                (pc, accumulator) = match operation.run(pc, accumulator) {
+                    Some(next) => next,
+                    None => break 'inner,
                 };
-                pc += 1;
             }
         }
-        -1
+        None
     }
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 enum Operation {
     Acc(isize),
     Jmp(isize),
     Nop(isize),
 }
 
+impl Operation {
+    /// The operation with `jmp` and `nop` swapped.
+    fn patched(self) -> Self {
+        match self {
+            Operation::Acc(v) => Operation::Acc(v),
+            Operation::Jmp(v) => Operation::Nop(v),
+            Operation::Nop(v) => Operation::Jmp(v),
+        }
+    }
+
+    /// The instruction to run next, and the accumulator, after running this operation at `pc`,
+    /// or `None` if either overflows.
+    fn run(self, pc: isize, accumulator: i64) -> Option<(isize, i64)> {
+        match self {
+            Operation::Acc(v) => Some((pc.checked_add(1)?, accumulator.checked_add(v as i64)?)),
+            Operation::Jmp(v) => Some((pc.checked_add(v)?, accumulator)),
+            Operation::Nop(_v) => Some((pc.checked_add(1)?, accumulator)),
+        }
+    }
+}
+
 impl FromStr for Operation {
-    type Err = ParseIntError;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         debug!("s: {}", s);
         let r = regex::Regex::new(r"^(?P<op>[^\s]+) (?P<arg>[+-]\d+)$").unwrap();
-        let c = r.captures(s).unwrap();
-        let arg = c.name("arg").unwrap().as_str().parse().unwrap();
-        let operation = match c.name("op").map(|v| v.as_str()) {
-            Some("acc") => Self::Acc(arg),
-            Some("jmp") => Self::Jmp(arg),
-            Some("nop") => Self::Nop(arg),
-            _ => unreachable!(),
+        let c = r
+            .captures(s)
+            .ok_or_else(|| ParseError::new("expected '<op> <+/-arg>'", s, 0))?;
+        let arg = c.name("arg").unwrap();
+        let arg = parse_at(arg.as_str(), arg.start())?;
+        let operation = match c.name("op").unwrap().as_str() {
+            "acc" => Self::Acc(arg),
+            "jmp" => Self::Jmp(arg),
+            "nop" => Self::Nop(arg),
+            op => return Err(ParseError::new("expected 'acc', 'jmp' or 'nop'", op, 0)),
         };
         Ok(operation)
     }
 }
+
+impl Display for Operation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Acc(arg) => write!(f, "acc {:+}", arg),
+            Self::Jmp(arg) => write!(f, "jmp {:+}", arg),
+            Self::Nop(arg) => write!(f, "nop {:+}", arg),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn operation() -> impl Strategy<Value = Operation> {
+        prop_oneof![
+            any::<isize>().prop_map(Operation::Acc),
+            any::<isize>().prop_map(Operation::Jmp),
+            any::<isize>().prop_map(Operation::Nop),
+        ]
+    }
+
+    #[test]
+    fn overflow_is_unsolved() {
+        let mut solution = Solution::parse("acc +9223372036854775807\nacc +1\n").unwrap();
+        solution.analyse();
+        assert!(!solution.answer_part1().is_solved());
+        assert!(!solution.answer_part2().is_solved());
+        let mut solution = Solution::parse("jmp +2\njmp +9223372036854775807\njmp -1\n").unwrap();
+        solution.analyse();
+        assert!(!solution.answer_part1().is_solved());
+    }
+
+    proptest! {
+        #[test]
+        fn display_round_trip(operation in operation()) {
+            prop_assert_eq!(Operation::from_str(&operation.to_string()).unwrap(), operation);
+        }
+    }
+}
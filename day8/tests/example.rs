@@ -0,0 +1,34 @@
+use day8::Solution;
+use puzzle::{Answer, Explanation, Part, Puzzle};
+
+#[test]
+fn example() {
+    let mut solution = Solution::parse(include_str!("../../inputs/2020/input.day8.small")).unwrap();
+    solution.analyse();
+    assert_eq!(solution.answer_part1(), Answer::Integer(5));
+    assert_eq!(solution.answer_part2(), Answer::Integer(8));
+}
+
+#[test]
+fn escape() {
+    let mut solution =
+        Solution::parse(include_str!("../../inputs/2020/input.day8.escape")).unwrap();
+    solution.analyse();
+    assert!(!solution.answer_part1().is_solved());
+    assert_eq!(solution.answer_part2(), Answer::Integer(2));
+}
+
+#[test]
+fn explain() {
+    let mut solution = Solution::parse(include_str!("../../inputs/2020/input.day8.small")).unwrap();
+    solution.analyse();
+    assert_eq!(
+        solution.explain(Part::Two),
+        Some(Explanation::record([
+            ("patched", 7_i64.into()),
+            ("was", "jmp -4".into()),
+            ("now", "nop -4".into()),
+            ("accumulator", 8_i64.into()),
+        ]))
+    );
+}
@@ -0,0 +1,60 @@
+use anyhow::{bail, ensure};
+use rand::seq::SliceRandom;
+use rand::Rng;
+use std::io::Write;
+
+const PREAMBLE: usize = 25;
+
+/// `size` numbers, each after the preamble being the sum of two of the 25 before it, except one
+/// three quarters of the way through, which is instead the sum of a contiguous run of earlier
+/// numbers.
+///
+/// Summing two earlier numbers at least doubles the smallest of them every 25 numbers, so inputs
+/// outgrow an `i64` after around 1500 numbers, even summing the smallest each time.
+pub fn input(size: usize, rng: &mut impl Rng, out: &mut dyn Write) -> anyhow::Result<()> {
+    ensure!(
+        size > PREAMBLE,
+        "expected more than {} numbers, to follow the preamble",
+        PREAMBLE
+    );
+    let mut numbers: Vec<i64> = (1..100).collect();
+    numbers.shuffle(rng);
+    numbers.truncate(PREAMBLE);
+    let invalid = PREAMBLE + (size - PREAMBLE) * 3 / 4;
+    while numbers.len() < size {
+        let mut window = numbers[numbers.len() - PREAMBLE..].to_vec();
+        window.sort_unstable();
+        window.dedup();
+        let next = match numbers.len() == invalid {
+            true => contiguous_sum(&numbers, &window, rng)?,
+            false => {
+                // Summing the smallest keeps the numbers from growing too fast
+                let smallest: Vec<&i64> = window.iter().take(6).collect();
+                let pair: Vec<&&i64> = smallest.choose_multiple(rng, 2).collect();
+                match pair[0].checked_add(**pair[1]) {
+                    Some(next) => next,
+                    None => bail!("numbers outgrew an i64 after {} numbers", numbers.len()),
+                }
+            }
+        };
+        numbers.push(next);
+    }
+    for number in numbers {
+        writeln!(out, "{}", number)?;
+    }
+    Ok(())
+}
+
+/// The sum of a random run of at least two `numbers`, which no two of `window` sum to, nor
+/// double any of them.
+fn contiguous_sum(numbers: &[i64], window: &[i64], rng: &mut impl Rng) -> anyhow::Result<i64> {
+    for _ in 0..1000 {
+        let start = rng.gen_range(0..numbers.len() - 1);
+        let end = rng.gen_range(start + 2..=numbers.len().min(start + 17));
+        let sum = numbers[start..end].iter().sum::<i64>();
+        if !window.iter().any(|a| window.contains(&(sum - a))) {
+            return Ok(sum);
+        }
+    }
+    bail!("no contiguous run of numbers has a sum which is invalid")
+}
@@ -1,22 +1,11 @@
 use log::debug;
-use std::cmp::{max, min};
+use puzzle::{check_lines, parse_at, Answer, Explanation, ParseError, Part, Puzzle};
+use rand::rngs::StdRng;
 use std::collections::HashSet;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::io::{BufRead, Write};
+use std::ops::RangeInclusive;
 
-pub fn load(filename: &str) -> anyhow::Result<Solution> {
-    let file = File::open(filename)?;
-
-    let reader = BufReader::new(file);
-    let mut solution = Solution::default();
-
-    for line in reader.lines() {
-        let line = line?;
-        let line = line.trim().parse().unwrap();
-        solution.add_data(line);
-    }
-    Ok(solution)
-}
+mod generate;
 
 #[derive(Debug, Default)]
 pub struct Solution {
@@ -25,18 +14,69 @@ pub struct Solution {
     answer_part2: Option<i64>,
 }
 
-impl Solution {
-    pub fn analyse(&mut self) {
+impl Puzzle for Solution {
+    const YEAR: u32 = 2020;
+    const DAY: u32 = 9;
+    const TITLE: &'static str = "Encoding Error";
+    const VERSION: &'static str = env!("CARGO_PKG_VERSION");
+
+    fn from_reader(reader: impl BufRead) -> anyhow::Result<Self> {
+        let mut solution = Solution::default();
+
+        for (idx, line) in reader.lines().enumerate() {
+            let line = line?;
+            let value = parse_at(line.trim(), 0).map_err(|e| e.at_line(idx + 1))?;
+            solution.add_data(value);
+        }
+        Ok(solution)
+    }
+
+    fn check(input: &str) -> Vec<ParseError> {
+        check_lines(input, |line| parse_at::<i64>(line.trim(), 0).map(drop))
+    }
+
+    fn generate(size: usize, rng: &mut StdRng, out: &mut dyn Write) -> anyhow::Result<()> {
+        generate::input(size, rng, out)
+    }
+
+    fn analyse(&mut self) {
         self.answer_part1 = self.analyse_part1();
         self.answer_part2 = self.analyse_part2();
     }
 
-    pub fn answer_part1(&self) -> Option<i64> {
-        self.answer_part1
+    fn answer_part1(&self) -> Answer {
+        self.answer_part1.into()
     }
 
-    pub fn answer_part2(&self) -> Option<i64> {
-        self.answer_part2
+    fn answer_part2(&self) -> Answer {
+        self.answer_part2.into()
+    }
+
+    fn explain(&self, part: Part) -> Option<Explanation> {
+        let explanation = match part {
+            Part::One => {
+                let invalid = self.answer_part1?;
+                Explanation::record([
+                    ("invalid", invalid.into()),
+                    (
+                        "position",
+                        self.data.iter().position(|&v| v == invalid)?.into(),
+                    ),
+                ])
+            }
+            Part::Two => {
+                let range = self.contiguous_range()?;
+                let numbers = &self.data[range.clone()];
+                Explanation::record([
+                    ("first", (*range.start()).into()),
+                    ("last", (*range.end()).into()),
+                    ("numbers", numbers.to_vec().into()),
+                    ("smallest", (*numbers.iter().min()?).into()),
+                    ("largest", (*numbers.iter().max()?).into()),
+                ])
+            }
+        };
+        Some(explanation)
     }
 }
 
@@ -68,46 +108,21 @@ impl Solution {
     }
 
     fn analyse_part2(&self) -> Option<i64> {
-        let v = self
-            .data
-            .iter()
-            .scan(Vec::new(), |state: &mut Vec<i64>, v| {
-                let last_state = state.iter().map(|v| v.to_owned()).collect::<HashSet<_>>();
-                state.push(*v);
-                if state.len() > 25 {
-                    state.drain(0..=0);
-                    Some((*v, last_state))
-                } else {
-                    Some((0, HashSet::new()))
-                }
-            })
-            .filter(|(_v, state)| !state.is_empty())
-            .collect::<Vec<_>>();
-
-        let probe = v
-            .iter()
-            .find(|(value, state)| !state.iter().any(|v| state.contains(&(value - v))))
-            .map(|(v, _state)| v.to_owned())
-            .unwrap();
+        let range = &self.data[self.contiguous_range()?];
+        Some(range.iter().min()? + range.iter().max()?)
+    }
 
-        for (idx, _v) in self.data.iter().enumerate() {
+    /// The positions of the first run of numbers summing to the invalid number.
+    fn contiguous_range(&self) -> Option<RangeInclusive<usize>> {
+        let probe = self.analyse_part1()?;
+        for idx in 0..self.data.len() {
             debug!("start idx: {}", idx);
             let mut total = 0;
-            let mut min_value = None;
-            let mut max_value = None;
             for i in idx..self.data.len() {
                 total += self.data[i];
-                min_value = match min_value {
-                    None => Some(self.data[i]),
-                    Some(v) => Some(min(v, self.data[i])),
-                };
-                max_value = match max_value {
-                    None => Some(self.data[i]),
-                    Some(v) => Some(max(v, self.data[i])),
-                };
                 if total == probe {
-                    debug!("matched {} to {} {:?}-{:?}", idx, i, min_value, max_value);
-                    return min_value.and_then(|v| max_value.map(|m| m + v));
+                    debug!("matched {} to {}", idx, i);
+                    return Some(idx..=i);
                 }
                 if total > probe {
                     debug!("break {}", idx);
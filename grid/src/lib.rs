@@ -0,0 +1,337 @@
+//! Dense two-dimensional grids of typed cells, as used by the map-based days.
+
+use puzzle::{check_lines, ParseError};
+use std::fmt::{Debug, Display};
+use std::io::BufRead;
+use std::ops::{Index, IndexMut};
+use std::str::FromStr;
+
+/// A grid cell that is read from, and rendered as, a single character.
+pub trait Cell: Copy {
+    /// The characters a cell may be parsed from, as shown in parse errors.
+    const EXPECTED: &'static str;
+
+    fn from_char(c: char) -> Option<Self>;
+    fn to_char(self) -> char;
+}
+
+/// What lies beyond an edge of the grid, along one axis.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Edge {
+    /// Nothing: coordinates beyond the edge are outside the grid.
+    #[default]
+    Clip,
+    /// The grid repeats: coordinates beyond the edge continue from the opposite edge.
+    Wrap,
+}
+
+/// Offsets to the 4 orthogonal neighbours of a cell.
+pub const ORTHOGONAL: [(i64, i64); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+
+/// Offsets to all 8 neighbours of a cell, including diagonals.
+pub const ADJACENT: [(i64, i64); 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (-1, 0),
+    (1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+];
+
+/// A rectangular grid of cells, stored row by row, indexed by `(x, y)` from the top left.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+    edges: (Edge, Edge),
+}
+
+impl<T> Default for Grid<T> {
+    fn default() -> Self {
+        Self {
+            width: 0,
+            height: 0,
+            cells: Vec::new(),
+            edges: Default::default(),
+        }
+    }
+}
+
+impl<T> Grid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Self
+    where
+        T: Clone,
+    {
+        Self {
+            width,
+            height,
+            cells: vec![fill; width * height],
+            edges: Default::default(),
+        }
+    }
+
+    /// Set what lies beyond the left/right (`x`) and top/bottom (`y`) edges.
+    pub fn with_edges(self, x: Edge, y: Edge) -> Self {
+        Self {
+            edges: (x, y),
+            ..self
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// The cell that `(x, y)` refers to under the edge policies, if any.
+    pub fn resolve(&self, x: i64, y: i64) -> Option<(usize, usize)> {
+        fn axis(v: i64, len: usize, edge: Edge) -> Option<usize> {
+            match edge {
+                _ if len == 0 => None,
+                Edge::Clip => usize::try_from(v).ok().filter(|&v| v < len),
+                Edge::Wrap => Some(v.rem_euclid(len as i64) as usize),
+            }
+        }
+        Some((
+            axis(x, self.width, self.edges.0)?,
+            axis(y, self.height, self.edges.1)?,
+        ))
+    }
+
+    pub fn get(&self, x: i64, y: i64) -> Option<&T> {
+        self.resolve(x, y).map(|point| &self[point])
+    }
+
+    pub fn get_mut(&mut self, x: i64, y: i64) -> Option<&mut T> {
+        self.resolve(x, y).map(move |point| &mut self[point])
+    }
+
+    /// Every cell along with its coordinates, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = ((usize, usize), &T)> {
+        let width = self.width;
+        self.cells
+            .iter()
+            .enumerate()
+            .map(move |(index, cell)| ((index % width, index / width), cell))
+    }
+
+    /// The cells at each of `offsets` from `(x, y)`, skipping any outside the grid.
+    pub fn neighbours<'a>(
+        &'a self,
+        x: usize,
+        y: usize,
+        offsets: &'a [(i64, i64)],
+    ) -> impl Iterator<Item = ((usize, usize), &'a T)> + 'a {
+        offsets.iter().filter_map(move |(dx, dy)| {
+            let point = self.resolve(x as i64 + dx, y as i64 + dy)?;
+            Some((point, &self[point]))
+        })
+    }
+
+    pub fn neighbours4(&self, x: usize, y: usize) -> impl Iterator<Item = ((usize, usize), &T)> {
+        self.neighbours(x, y, &ORTHOGONAL)
+    }
+
+    pub fn neighbours8(&self, x: usize, y: usize) -> impl Iterator<Item = ((usize, usize), &T)> {
+        self.neighbours(x, y, &ADJACENT)
+    }
+
+    /// The cells seen looking from `(x, y)` in steps of `(dx, dy)`, excluding `(x, y)` itself.
+    ///
+    /// Ends at a clipped edge, or on arriving back at `(x, y)` around wrapped edges.
+    pub fn line_of_sight(
+        &self,
+        x: usize,
+        y: usize,
+        (dx, dy): (i64, i64),
+    ) -> impl Iterator<Item = ((usize, usize), &T)> {
+        let mut position = (x, y);
+        std::iter::from_fn(move || {
+            position = self.resolve(position.0 as i64 + dx, position.1 as i64 + dy)?;
+            if position == (x, y) {
+                return None;
+            }
+            Some((position, &self[position]))
+        })
+    }
+
+    /// A grid of the same shape and edges, with each cell computed from the corresponding cell here.
+    pub fn map<U>(&self, mut f: impl FnMut((usize, usize), &T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.iter().map(|(point, cell)| f(point, cell)).collect(),
+            edges: self.edges,
+        }
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, (x, y): (usize, usize)) -> &T {
+        assert!(
+            x < self.width && y < self.height,
+            "({}, {}) is outside the grid",
+            x,
+            y
+        );
+        &self.cells[y * self.width + x]
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, (x, y): (usize, usize)) -> &mut T {
+        assert!(
+            x < self.width && y < self.height,
+            "({}, {}) is outside the grid",
+            x,
+            y
+        );
+        &mut self.cells[y * self.width + x]
+    }
+}
+
+impl<T: Cell> Grid<T> {
+    /// Read a grid with one row per line.
+    pub fn from_reader(reader: impl BufRead) -> anyhow::Result<Self> {
+        let mut grid = Self::default();
+        for (y, line) in reader.lines().enumerate() {
+            grid.push_row(&line?).map_err(|e| e.at_line(y + 1))?;
+        }
+        Ok(grid)
+    }
+
+    /// Check every row of a grid, reporting each row which is not as wide as the first or holds
+    /// a character which is not a cell.
+    pub fn check(input: &str) -> Vec<ParseError> {
+        let mut grid = Self::default();
+        check_lines(input, |line| grid.push_row(line))
+    }
+
+    /// Append a row of cells, which must be as wide as those before it.
+    pub fn push_row(&mut self, line: &str) -> Result<(), ParseError> {
+        let width = line.chars().count();
+        if self.height == 0 {
+            self.width = width;
+        } else if width != self.width {
+            return Err(ParseError::new(
+                format!("expected a row of width {}", self.width),
+                line,
+                0,
+            ));
+        }
+        let row = line
+            .char_indices()
+            .map(|(offset, c)| {
+                T::from_char(c).ok_or_else(|| {
+                    ParseError::new(format!("expected {}", T::EXPECTED), &c.to_string(), offset)
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        self.cells.extend(row);
+        self.height += 1;
+        Ok(())
+    }
+}
+
+impl<T: Cell> FromStr for Grid<T> {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut grid = Self::default();
+        for (y, line) in s.lines().enumerate() {
+            grid.push_row(line).map_err(|e| e.at_line(y + 1))?;
+        }
+        Ok(grid)
+    }
+}
+
+impl<T: Cell> Display for Grid<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for row in self.cells.chunks(self.width.max(1)) {
+            let row: String = row.iter().map(|cell| cell.to_char()).collect();
+            writeln!(f, "{}", row)?;
+        }
+        Ok(())
+    }
+}
+
+impl<T: Cell> Debug for Grid<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Grid {}x{} {:?}\n{}",
+            self.width, self.height, self.edges, self
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    enum Square {
+        Open,
+        Tree,
+    }
+
+    impl Cell for Square {
+        const EXPECTED: &'static str = "'#' or '.'";
+
+        fn from_char(c: char) -> Option<Self> {
+            match c {
+                '.' => Some(Square::Open),
+                '#' => Some(Square::Tree),
+                _ => None,
+            }
+        }
+
+        fn to_char(self) -> char {
+            match self {
+                Square::Open => '.',
+                Square::Tree => '#',
+            }
+        }
+    }
+
+    #[test]
+    fn parse_and_render() {
+        let grid: Grid<Square> = "#..\n.#.\n".parse().unwrap();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[(1, 1)], Square::Tree);
+        assert_eq!(grid.to_string(), "#..\n.#.\n");
+
+        let e = "#..\n.x.\n".parse::<Grid<Square>>().unwrap_err();
+        assert_eq!((e.line, e.column), (Some(2), 2));
+
+        let problems = Grid::<Square>::check("#..\n.x.\n#.\n...\n");
+        let lines: Vec<_> = problems.iter().map(|e| e.line).collect();
+        assert_eq!(lines, vec![Some(2), Some(3)]);
+        let e = "#..\n.#\n".parse::<Grid<Square>>().unwrap_err();
+        assert_eq!(e.message, "expected a row of width 3");
+    }
+
+    #[test]
+    fn neighbours_and_edges() {
+        let grid: Grid<Square> = "#..\n.#.\n...\n".parse().unwrap();
+        assert_eq!(grid.neighbours8(0, 0).count(), 3);
+        assert_eq!(grid.neighbours4(1, 1).count(), 4);
+        assert_eq!(grid.get(-1, 0), None);
+
+        let grid = grid.with_edges(Edge::Wrap, Edge::Clip);
+        assert_eq!(grid.neighbours8(0, 0).count(), 5);
+        assert_eq!(grid.get(-2, 1), Some(&Square::Tree));
+        let seen: Vec<_> = grid.line_of_sight(0, 0, (2, 1)).map(|(p, _)| p).collect();
+        assert_eq!(seen, [(2, 1), (1, 2)]);
+        let seen: Vec<_> = grid.line_of_sight(0, 0, (1, 0)).map(|(p, _)| p).collect();
+        assert_eq!(seen, [(1, 0), (2, 0)]);
+    }
+}
@@ -0,0 +1,68 @@
+use num_bigint::BigInt;
+use std::fmt::Display;
+use std::time::Duration;
+
+/// A puzzle's answer to one part.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Answer {
+    Integer(i64),
+    /// An integer too large, or potentially too large, for an `i64`.
+    BigInteger(BigInt),
+    Text(String),
+    /// No answer was found, for the given reason.
+    Unsolved(String),
+    /// The solver gave up when its time ran out.
+    TimedOut(Duration),
+}
+
+impl Answer {
+    pub fn is_solved(&self) -> bool {
+        !matches!(self, Answer::Unsolved(_) | Answer::TimedOut(_))
+    }
+}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Answer::Integer(value) => write!(f, "{}", value),
+            Answer::BigInteger(value) => write!(f, "{}", value),
+            Answer::Text(text) => write!(f, "{}", text),
+            Answer::Unsolved(reason) => write!(f, "unsolved ({})", reason),
+            Answer::TimedOut(timeout) => write!(f, "timed out after {:?}", timeout),
+        }
+    }
+}
+
+impl From<i64> for Answer {
+    fn from(value: i64) -> Self {
+        Answer::Integer(value)
+    }
+}
+
+impl From<BigInt> for Answer {
+    fn from(value: BigInt) -> Self {
+        Answer::BigInteger(value)
+    }
+}
+
+impl From<String> for Answer {
+    fn from(text: String) -> Self {
+        Answer::Text(text)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(text: &str) -> Self {
+        Answer::Text(text.to_string())
+    }
+}
+
+/// `None` means no answer was found.
+impl<T: Into<Answer>> From<Option<T>> for Answer {
+    fn from(answer: Option<T>) -> Self {
+        match answer {
+            Some(answer) => answer.into(),
+            None => Answer::Unsolved("no answer found".to_string()),
+        }
+    }
+}
@@ -0,0 +1,133 @@
+use std::fmt::Display;
+use std::str::FromStr;
+
+/// A problem found in puzzle input, located as precisely as the parser could manage.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub file: Option<String>,
+    /// 1-based line number, once known.
+    pub line: Option<usize>,
+    /// 1-based column within the line.
+    pub column: usize,
+    /// The offending text.
+    pub text: String,
+    pub message: String,
+}
+
+impl ParseError {
+    /// `text` (starting at byte `offset` within its line) is not valid.
+    pub fn new(message: impl Into<String>, text: &str, offset: usize) -> Self {
+        Self {
+            file: None,
+            line: None,
+            column: offset + 1,
+            text: text.to_string(),
+            message: message.into(),
+        }
+    }
+
+    pub fn at_line(self, line: usize) -> Self {
+        Self {
+            line: Some(line),
+            ..self
+        }
+    }
+
+    pub fn in_file(self, file: &str) -> Self {
+        Self {
+            file: Some(file.to_string()),
+            ..self
+        }
+    }
+
+    /// Shift the column of an error raised on a substring to its position in the whole line.
+    pub fn offset_by(self, offset: usize) -> Self {
+        Self {
+            column: self.column + offset,
+            ..self
+        }
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // A column means little without its line, as with problems of the input as a whole
+        match (&self.file, self.line) {
+            (Some(file), Some(line)) => write!(f, "{}:{}:{}: ", file, line, self.column)?,
+            (Some(file), None) => write!(f, "{}: ", file)?,
+            (None, Some(line)) => write!(f, "{}:{}: ", line, self.column)?,
+            (None, None) => {}
+        }
+        write!(f, "{}", self.message)?;
+        if !self.text.is_empty() {
+            write!(f, ": '{}'", self.text)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Attribute an error reading input to the file it came from: a [`ParseError`] gains the file in
+/// its location, and any other error the file as context.
+pub fn in_file(error: anyhow::Error, file: &str) -> anyhow::Error {
+    match error.downcast::<ParseError>() {
+        Ok(e) => e.in_file(file).into(),
+        Err(e) => e.context(format!("loading '{}'", file)),
+    }
+}
+
+/// Parse `text`, found at byte `offset` within its line, reporting failure as a [`ParseError`].
+pub fn parse_at<T>(text: &str, offset: usize) -> Result<T, ParseError>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    text.parse()
+        .map_err(|e: T::Err| ParseError::new(e.to_string(), text, offset))
+}
+
+/// Check every line of `input` in turn, collecting each problem found, located by its line.
+pub fn check_lines(
+    input: &str,
+    mut check: impl FnMut(&str) -> Result<(), ParseError>,
+) -> Vec<ParseError> {
+    input
+        .lines()
+        .enumerate()
+        .filter_map(|(idx, line)| check(line).err().map(|e| e.at_line(idx + 1)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display_location() {
+        let e = ParseError::new("invalid digit found in string", "1x", 3)
+            .at_line(7)
+            .in_file("input.day1.full");
+        assert_eq!(
+            e.to_string(),
+            "input.day1.full:7:4: invalid digit found in string: '1x'"
+        );
+        let e = ParseError::new("expected a rule 0", "", 0).in_file("input.day19.full");
+        assert_eq!(e.to_string(), "input.day19.full: expected a rule 0");
+    }
+
+    #[test]
+    fn parse_at_column() {
+        let e = parse_at::<i64>("abc", 5).unwrap_err();
+        assert_eq!(e.column, 6);
+        assert_eq!(e.text, "abc");
+        assert_eq!(parse_at::<i64>("-12", 0), Ok(-12));
+    }
+
+    #[test]
+    fn check_every_line() {
+        let problems = check_lines("1\nx\n3\ny", |line| parse_at::<i64>(line, 0).map(drop));
+        let lines: Vec<_> = problems.iter().map(|e| e.line).collect();
+        assert_eq!(lines, vec![Some(2), Some(4)]);
+    }
+}
@@ -0,0 +1,168 @@
+use std::fmt::Display;
+
+/// How an answer was reached, as a tree of facts, for the runner to print as text or JSON.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Explanation {
+    Integer(i64),
+    Text(String),
+    List(Vec<Explanation>),
+    /// Named facts, in the order given.
+    Record(Vec<(String, Explanation)>),
+}
+
+impl Explanation {
+    pub fn record<'a>(facts: impl IntoIterator<Item = (&'a str, Explanation)>) -> Self {
+        Explanation::Record(
+            facts
+                .into_iter()
+                .map(|(name, fact)| (name.to_string(), fact))
+                .collect(),
+        )
+    }
+
+    fn is_scalar(&self) -> bool {
+        matches!(self, Explanation::Integer(_) | Explanation::Text(_))
+    }
+
+    /// Write the explanation after a name, on the same line if it fits there and otherwise on
+    /// the lines below, indented by `indent`.
+    fn write(&self, f: &mut std::fmt::Formatter<'_>, indent: usize) -> std::fmt::Result {
+        match self {
+            Explanation::List(items) if !items.iter().all(Explanation::is_scalar) => {
+                writeln!(f)?;
+                Self::write_items(f, items, indent)
+            }
+            Explanation::Record(facts) => {
+                writeln!(f)?;
+                Self::write_facts(f, facts, indent, indent)
+            }
+            _ => writeln!(f, " {}", self.inline()),
+        }
+    }
+
+    /// Write each item after a `-`, a record's first fact on the same line as its `-`.
+    fn write_items(
+        f: &mut std::fmt::Formatter<'_>,
+        items: &[Explanation],
+        indent: usize,
+    ) -> std::fmt::Result {
+        for item in items {
+            write!(f, "{:indent$}-", "", indent = indent)?;
+            match item {
+                Explanation::Record(facts) => {
+                    Self::write_facts(f, facts, 1, indent + 2)?;
+                }
+                item => item.write(f, indent + 2)?,
+            }
+        }
+        Ok(())
+    }
+
+    /// Write each fact as `name: value`, the first indented by `first` and the rest by
+    /// `indent`.
+    fn write_facts(
+        f: &mut std::fmt::Formatter<'_>,
+        facts: &[(String, Explanation)],
+        first: usize,
+        indent: usize,
+    ) -> std::fmt::Result {
+        for (idx, (name, fact)) in facts.iter().enumerate() {
+            let pad = if idx == 0 { first } else { indent };
+            write!(f, "{:pad$}{}:", "", name, pad = pad)?;
+            fact.write(f, indent + 2)?;
+        }
+        Ok(())
+    }
+
+    fn inline(&self) -> String {
+        match self {
+            Explanation::Integer(value) => value.to_string(),
+            Explanation::Text(text) => text.clone(),
+            Explanation::List(items) => {
+                let items: Vec<String> = items.iter().map(Explanation::inline).collect();
+                format!("[{}]", items.join(", "))
+            }
+            Explanation::Record(_) => unreachable!("records are never written inline"),
+        }
+    }
+}
+
+/// One fact per line, as `name: value`, with nested facts indented below their name.
+impl Display for Explanation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Explanation::Record(facts) => Self::write_facts(f, facts, 0, 0),
+            Explanation::List(items) if !items.iter().all(Explanation::is_scalar) => {
+                Self::write_items(f, items, 0)
+            }
+            _ => writeln!(f, "{}", self.inline()),
+        }
+    }
+}
+
+impl From<i64> for Explanation {
+    fn from(value: i64) -> Self {
+        Explanation::Integer(value)
+    }
+}
+
+impl From<usize> for Explanation {
+    fn from(value: usize) -> Self {
+        Explanation::Integer(value as i64)
+    }
+}
+
+impl From<String> for Explanation {
+    fn from(text: String) -> Self {
+        Explanation::Text(text)
+    }
+}
+
+impl From<&str> for Explanation {
+    fn from(text: &str) -> Self {
+        Explanation::Text(text.to_string())
+    }
+}
+
+impl<T: Into<Explanation>> From<Vec<T>> for Explanation {
+    fn from(items: Vec<T>) -> Self {
+        Explanation::List(items.into_iter().map(Into::into).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display() {
+        let explanation = Explanation::record([
+            ("entries", vec![1721_i64, 299].into()),
+            (
+                "tree",
+                Explanation::record([
+                    ("rule", 0_i64.into()),
+                    (
+                        "parts",
+                        vec![
+                            Explanation::record([("rule", 4_i64.into()), ("text", "a".into())]),
+                            Explanation::record([("rule", 5_i64.into()), ("text", "b".into())]),
+                        ]
+                        .into(),
+                    ),
+                ]),
+            ),
+        ]);
+        assert_eq!(
+            explanation.to_string(),
+            "entries: [1721, 299]\n\
+             tree:\n  \
+               rule: 0\n  \
+               parts:\n    \
+                 - rule: 4\n      \
+                   text: a\n    \
+                 - rule: 5\n      \
+                   text: b\n"
+        );
+    }
+}
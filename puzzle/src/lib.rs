@@ -0,0 +1,222 @@
+use anyhow::Context;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use std::fmt::{Debug, Display};
+use std::fs::File;
+use std::io::{BufRead, BufReader, Write};
+use std::str::FromStr;
+
+mod answer;
+mod cancel;
+mod error;
+mod explanation;
+
+pub use answer::Answer;
+pub use cancel::Cancel;
+pub use error::{check_lines, in_file, parse_at, ParseError};
+pub use explanation::Explanation;
+
+/// Common shape of every day's solution, so that tooling can drive any day uniformly.
+pub trait Puzzle: Debug + Sized {
+    /// Year of the advent calendar this puzzle was published in.
+    const YEAR: u32;
+    /// Day of the advent calendar this puzzle was published on.
+    const DAY: u32;
+    /// Title of the puzzle, as published.
+    const TITLE: &'static str;
+    /// Version of the crate solving the puzzle, `env!("CARGO_PKG_VERSION")`, so that answers
+    /// cached by an older version are not served.
+    const VERSION: &'static str;
+
+    /// Read the puzzle from its input.
+    fn from_reader(reader: impl BufRead) -> anyhow::Result<Self>;
+
+    /// Parse the puzzle from input already held in memory.
+    fn parse(input: &str) -> anyhow::Result<Self> {
+        Self::from_reader(input.as_bytes())
+    }
+
+    /// Load the puzzle from the named input file.
+    fn load(filename: &str) -> anyhow::Result<Self> {
+        let file = File::open(filename).context(format!("loading '{}'", filename))?;
+        Self::from_reader(BufReader::new(file)).map_err(|e| in_file(e, filename))
+    }
+
+    /// Check the input against the day's grammar without solving it, reporting every problem
+    /// found. By default the input is parsed, which stops at the first problem; days override
+    /// this to carry on past it, and to catch input the solver would choke on.
+    fn check(input: &str) -> Vec<ParseError> {
+        match Self::parse(input) {
+            Ok(_) => Vec::new(),
+            Err(e) => match e.downcast::<ParseError>() {
+                Ok(e) => vec![e],
+                Err(e) => vec![ParseError::new(format!("{:#}", e), "", 0)],
+            },
+        }
+    }
+
+    /// Write a synthetic input of the given size, for stress testing, making every random choice
+    /// with `rng` so that a seed always gives the same input.
+    ///
+    /// What `size` counts (lines, rows, tickets, ...) is up to each day.
+    fn generate(_size: usize, _rng: &mut StdRng, _out: &mut dyn Write) -> anyhow::Result<()> {
+        anyhow::bail!("{} day {} has no input generator", Self::YEAR, Self::DAY)
+    }
+
+    /// Hand the solution a token to check in any loop which might never end on bad input, so
+    /// that it gives up when asked to. Ignored by default.
+    fn set_cancel(&mut self, _cancel: Cancel) {}
+
+    /// Perform any up-front work needed before the answers are available.
+    fn analyse(&mut self);
+
+    fn answer_part1(&self) -> Answer;
+
+    fn answer_part2(&self) -> Answer;
+
+    /// How the answer to a part was reached, for those days that can say. Only asked for once
+    /// the part has been answered.
+    fn explain(&self, _part: Part) -> Option<Explanation> {
+        None
+    }
+
+    /// A picture of the puzzle as a part left it, as lines of text, for those days with
+    /// something to draw. Only asked for once the part has been answered.
+    fn render(&self, _part: Part) -> Option<String> {
+        None
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub const ALL: [Part; 2] = [Part::One, Part::Two];
+}
+
+impl Display for Part {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Part::One => write!(f, "part1"),
+            Part::Two => write!(f, "part2"),
+        }
+    }
+}
+
+impl FromStr for Part {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "1" | "part1" => Ok(Part::One),
+            "2" | "part2" => Ok(Part::Two),
+            _ => anyhow::bail!("unknown part '{}', expected 1 or 2", s),
+        }
+    }
+}
+
+/// Object-safe view of a loaded [`Puzzle`], for when the day is only known at runtime.
+pub trait Solver: Debug {
+    fn set_cancel(&mut self, cancel: Cancel);
+
+    fn analyse(&mut self);
+
+    fn answer(&self, part: Part) -> Answer;
+
+    fn explain(&self, part: Part) -> Option<Explanation>;
+
+    fn render(&self, part: Part) -> Option<String>;
+}
+
+impl<P: Puzzle> Solver for P {
+    fn set_cancel(&mut self, cancel: Cancel) {
+        Puzzle::set_cancel(self, cancel)
+    }
+
+    fn analyse(&mut self) {
+        Puzzle::analyse(self)
+    }
+
+    fn answer(&self, part: Part) -> Answer {
+        match part {
+            Part::One => self.answer_part1(),
+            Part::Two => self.answer_part2(),
+        }
+    }
+
+    fn explain(&self, part: Part) -> Option<Explanation> {
+        Puzzle::explain(self, part)
+    }
+
+    fn render(&self, part: Part) -> Option<String> {
+        Puzzle::render(self, part)
+    }
+}
+
+/// Type-erased handle on a day's [`Puzzle`] implementation.
+#[derive(Clone, Copy)]
+pub struct Day {
+    pub year: u32,
+    pub day: u32,
+    pub title: &'static str,
+    pub version: &'static str,
+    load: fn(&str) -> anyhow::Result<Box<dyn Solver>>,
+    from_reader: fn(&mut dyn BufRead) -> anyhow::Result<Box<dyn Solver>>,
+    check: fn(&str) -> Vec<ParseError>,
+    generate: fn(usize, &mut StdRng, &mut dyn Write) -> anyhow::Result<()>,
+}
+
+impl Day {
+    pub fn of<P: Puzzle + 'static>() -> Self {
+        Self {
+            year: P::YEAR,
+            day: P::DAY,
+            title: P::TITLE,
+            version: P::VERSION,
+            load: |filename| Ok(Box::new(P::load(filename)?)),
+            from_reader: |reader| Ok(Box::new(P::from_reader(reader)?)),
+            check: P::check,
+            generate: P::generate,
+        }
+    }
+
+    pub fn load(&self, filename: &str) -> anyhow::Result<Box<dyn Solver>> {
+        (self.load)(filename)
+    }
+
+    pub fn from_reader(&self, reader: &mut dyn BufRead) -> anyhow::Result<Box<dyn Solver>> {
+        (self.from_reader)(reader)
+    }
+
+    pub fn parse(&self, input: &str) -> anyhow::Result<Box<dyn Solver>> {
+        self.from_reader(&mut input.as_bytes())
+    }
+
+    pub fn check(&self, input: &str) -> Vec<ParseError> {
+        (self.check)(input)
+    }
+
+    pub fn generate(&self, size: usize, seed: u64, out: &mut dyn Write) -> anyhow::Result<()> {
+        (self.generate)(size, &mut StdRng::seed_from_u64(seed), out)
+    }
+}
+
+impl Debug for Day {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Day")
+            .field("year", &self.year)
+            .field("day", &self.day)
+            .field("title", &self.title)
+            .finish()
+    }
+}
+
+/// The year and day, as in `2020 day 13`.
+impl Display for Day {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} day {}", self.year, self.day)
+    }
+}
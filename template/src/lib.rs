@@ -1,16 +1,5 @@
-use std::fs::File;
-use std::io::{BufRead, BufReader};
-
-pub fn load(filename: &str) -> anyhow::Result<Solution> {
-    let file = File::open(filename)?;
-
-    let mut reader = BufReader::new(file);
-
-    let mut line = String::new();
-    reader.read_line(&mut line)?;
-
-    Ok(Solution::new())
-}
+use puzzle::{Answer, Puzzle};
+use std::io::BufRead;
 
 #[derive(Debug)]
 pub struct Solution {}
@@ -19,14 +8,28 @@ impl Solution {
     fn new() -> Self {
         Self {}
     }
+}
+
+impl Puzzle for Solution {
+    const YEAR: u32 = 0;
+    const DAY: u32 = 0;
+    const TITLE: &'static str = "Template";
+    const VERSION: &'static str = env!("CARGO_PKG_VERSION");
+
+    fn from_reader(mut reader: impl BufRead) -> anyhow::Result<Self> {
+        let mut line = String::new();
+        reader.read_line(&mut line)?;
+
+        Ok(Solution::new())
+    }
 
-    pub fn analyse(&mut self) {}
+    fn analyse(&mut self) {}
 
-    pub fn answer_part1(&self) -> Option<i64> {
-        None
+    fn answer_part1(&self) -> Answer {
+        Answer::Unsolved("not implemented".to_string())
     }
 
-    pub fn answer_part2(&self) -> Option<i64> {
-        None
+    fn answer_part2(&self) -> Answer {
+        Answer::Unsolved("not implemented".to_string())
     }
 }